@@ -1,14 +1,224 @@
-use byteorder::{ByteOrder, BigEndian, WriteBytesExt};
+use core::cmp::max;
+use net::{Ipv4Addr, Ipv6Addr};
+use alloc::vec::Vec;
 
-use {Opcode, ResponseCode, Header, QueryType, QueryClass};
+use byteorder::{ByteOrder, BigEndian};
+
+use {Opcode, ResponseCode, Header, QueryType, QueryClass, Type, Class};
+use {Packet, Name, Question, ResourceRecord, OwnedName, Error, BuildError};
+use {OwnedRecord};
+use rdata::Writer;
+use rdata::opt;
+
+/// DNSSEC OK bit in the flags of OPT record (RFC 3225)
+const EDNS_DO_FLAG: u16 = 0x8000;
 
 /// Allows to build a DNS packet
 ///
 /// Both query and answer packets may be built with this interface, although,
 /// much of functionality is not implemented yet.
+///
+/// The packet is written into a `Vec<u8>` by default. Use `query_in` and
+/// `response_in` to write into another `Buffer`, e.g. a `SliceBuffer`
+/// over a caller-provided slice.
+///
+/// Methods adding data panic on invalid input. Their `try_add_*`
+/// counterparts return a `BuildError` instead and discard the whole
+/// question or record, which is also how a fixed-size buffer being full
+/// is reported.
 #[derive(Debug)]
-pub struct Builder {
-    buf: Vec<u8>,
+pub struct Builder<B: Buffer = Vec<u8>> {
+    buf: B,
+    /// Offsets of labels written so far, used as compression targets
+    labels: Vec<u16>,
+    /// OPT pseudo-RR appended to the additional section by `build()`
+    edns: Option<Edns>,
+    /// Whether names may be written as compression pointers
+    compress: bool,
+    /// Set when a write didn't fit into the buffer, further writes are
+    /// ignored until the current question or record is discarded
+    full: bool,
+    /// Offsets of resource records, except OPT, used for truncation
+    records: Vec<usize>,
+}
+
+/// Numbers of records dropped from each section by `Builder::truncate_to`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[allow(missing_docs)]  // self-descriptive
+pub struct Truncated {
+    pub answers: u16,
+    pub nameservers: u16,
+    pub additional: u16,
+}
+
+/// A sink the `Builder` writes the packet to
+///
+/// This is similar to `BufMut` from the `bytes` crate, but bytes already
+/// written can also be modified (to update counts in the header) and
+/// discarded (to roll back a record which doesn't fit).
+pub trait Buffer {
+    /// Returns bytes written so far
+    fn as_bytes(&self) -> &[u8];
+    /// Returns bytes written so far for modification
+    fn as_bytes_mut(&mut self) -> &mut [u8];
+    /// Returns number of bytes which can be appended
+    fn remaining_mut(&self) -> usize;
+    /// Appends bytes, `data` is never longer than `remaining_mut()`
+    fn put_slice(&mut self, data: &[u8]);
+    /// Discards everything after the first `len` bytes
+    fn truncate(&mut self, len: usize);
+}
+
+/// A `Buffer` writing into a fixed-size slice
+#[derive(Debug)]
+pub struct SliceBuffer<'a> {
+    data: &'a mut [u8],
+    len: usize,
+}
+
+impl Buffer for Vec<u8> {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self
+    }
+    fn remaining_mut(&self) -> usize {
+        isize::MAX as usize - self.len()
+    }
+    fn put_slice(&mut self, data: &[u8]) {
+        self.extend_from_slice(data);
+    }
+    fn truncate(&mut self, len: usize) {
+        Vec::truncate(self, len);
+    }
+}
+
+impl<'a> SliceBuffer<'a> {
+    /// Creates an empty buffer, the packet may occupy the whole slice
+    pub fn new(data: &'a mut [u8]) -> SliceBuffer<'a> {
+        SliceBuffer { data, len: 0 }
+    }
+    /// Returns number of bytes written
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns true if nothing is written yet
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Returns the part of the slice containing written bytes
+    pub fn into_bytes(self) -> &'a mut [u8] {
+        &mut self.data[..self.len]
+    }
+}
+
+impl<'a> Buffer for SliceBuffer<'a> {
+    fn as_bytes(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        &mut self.data[..self.len]
+    }
+    fn remaining_mut(&self) -> usize {
+        self.data.len() - self.len
+    }
+    fn put_slice(&mut self, data: &[u8]) {
+        let end = self.len + data.len();
+        self.data[self.len..end].copy_from_slice(data);
+        self.len = end;
+    }
+    fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            self.len = len;
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Edns {
+    udp: u16,
+    extrcode: u8,
+    version: u8,
+    flags: u16,
+    /// Already serialized option TLVs
+    options: Vec<u8>,
+}
+
+/// Data of a resource record to be written by the `Builder`
+///
+/// Domain names are passed as dotted strings, the same way as for
+/// `Builder::add_question`.
+#[derive(Debug, Clone, Copy)]
+#[allow(missing_docs)]  // fields are the same as in `rdata` records
+pub enum RecordData<'a> {
+    A(Ipv4Addr),
+    AAAA(Ipv6Addr),
+    CNAME(&'a str),
+    MX {
+        preference: u16,
+        exchange: &'a str,
+    },
+    NS(&'a str),
+    PTR(&'a str),
+    SOA {
+        primary_ns: &'a str,
+        mailbox: &'a str,
+        serial: u32,
+        refresh: u32,
+        retry: u32,
+        expire: u32,
+        minimum_ttl: u32,
+    },
+    SRV {
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: &'a str,
+    },
+    /// A list of character strings, each at most 255 bytes long
+    ///
+    /// An empty list is written as a single empty string, because
+    /// TXT record must contain at least one string.
+    TXT(&'a [&'a [u8]]),
+    /// Record data of any type already serialized in wire format
+    ///
+    /// Domain names in the data must not be compressed.
+    Raw(Type, &'a [u8]),
+}
+
+impl<'a> RecordData<'a> {
+    /// Returns record type as enum
+    pub fn type_code(&self) -> Type {
+        match *self {
+            RecordData::A(..)       => Type::A,
+            RecordData::AAAA(..)    => Type::AAAA,
+            RecordData::CNAME(..)   => Type::CNAME,
+            RecordData::MX { .. }   => Type::MX,
+            RecordData::NS(..)      => Type::NS,
+            RecordData::PTR(..)     => Type::PTR,
+            RecordData::SOA { .. }  => Type::SOA,
+            RecordData::SRV { .. }  => Type::SRV,
+            RecordData::TXT(..)     => Type::TXT,
+            RecordData::Raw(typ, _) => typ,
+        }
+    }
+}
+
+impl Edns {
+    fn echo(query: &opt::Record) -> Edns {
+        Edns {
+            udp: query.udp,
+            extrcode: 0,
+            version: 0,
+            flags: query.flags & EDNS_DO_FLAG,
+            options: Vec::new(),
+        }
+    }
+    /// Size of the serialized OPT record
+    fn record_len(&self) -> usize {
+        11 + self.options.len()
+    }
 }
 
 impl Builder {
@@ -17,7 +227,115 @@ impl Builder {
     /// Initially all sections are empty. You're expected to fill
     /// the questions section with `add_question`
     pub fn new_query(id: u16, recursion: bool) -> Builder {
-        let mut buf = Vec::with_capacity(512);
+        Builder::query_in(Vec::with_capacity(512), id, recursion)
+            .expect("vector never overflows")
+    }
+    /// Creates a response to the query
+    ///
+    /// Copies id, opcode, recursion desired and checking disabled flags
+    /// and the whole questions section from the query. If the query
+    /// contains an OPT record, the response will contain one too, with
+    /// the same UDP payload size and DNSSEC OK flag.
+    ///
+    /// Response code is `NoError`, use `set_response_code`,
+    /// `set_authoritative` and `set_recursion_available` to change
+    /// the header and `add_answer` and friends to fill the rest of
+    /// the packet.
+    pub fn new_response(query: &Packet) -> Builder {
+        Builder::response_in(Vec::with_capacity(512), query)
+            .expect("vector never overflows")
+    }
+    /// Creates a builder containing the whole parsed packet
+    ///
+    /// The header, including flags and response code, all sections and
+    /// the OPT record are copied, so more records may be appended before
+    /// calling `build()`. Names are compressed regardless of how they were
+    /// written in the original packet.
+    ///
+    /// # Panics
+    ///
+    /// Same as `Packet::write_to`
+    pub fn from_packet(packet: &Packet) -> Builder {
+        Builder::copy_packet(packet, true)
+    }
+    fn copy_packet(packet: &Packet, compress: bool) -> Builder {
+        let head = Header {
+            questions: 0,
+            answers: 0,
+            nameservers: 0,
+            additional: 0,
+            .. packet.header
+        };
+        let mut builder = Builder::with_header(Vec::with_capacity(512), &head)
+            .expect("vector never overflows");
+        builder.compress = compress;
+        builder.edns = packet.opt.as_ref().map(|opt| Edns {
+            udp: opt.udp,
+            extrcode: opt.extrcode,
+            version: opt.version,
+            flags: opt.flags,
+            options: opt.data.to_vec(),
+        });
+        for q in &packet.questions {
+            unwrap_built(builder.copy_question(q), "a question");
+        }
+        for rr in &packet.answers {
+            unwrap_built(builder.copy_record(rr, 6), "an answer");
+        }
+        for rr in &packet.nameservers {
+            unwrap_built(builder.copy_record(rr, 8), "a nameserver");
+        }
+        for rr in &packet.additional {
+            unwrap_built(builder.copy_record(rr, 10), "an additional record");
+        }
+        builder
+    }
+    /// Returns the final packet
+    ///
+    /// When packet is not truncated method returns `Ok(packet)`. If
+    /// packet is truncated the method returns `Err(packet)`. In both
+    /// cases the packet is fully valid.
+    ///
+    /// In the server implementation you may use
+    /// `x.build().unwrap_or_else(|x| x)`.
+    ///
+    /// In the client implementation it's probably unwise to send truncated
+    /// packet, as it doesn't make sense. Even panicking may be more
+    /// appropriate.
+    ///
+    /// The size limit is 512 bytes or UDP payload size advertised in
+    /// the OPT record, if any. Records which don't fit are dropped as
+    /// described in `truncate_to`, and the packet is considered truncated
+    /// if TC flag is set. If questions alone don't fit the limit, TC flag
+    /// is set and the oversized packet is returned as `Err`. For TCP use
+    /// `truncate_to(65535)` and `finish()`.
+    pub fn build(mut self) -> Result<Vec<u8>,Vec<u8>> {
+        let mut limit = 512;
+        if let Some(ref edns) = self.edns {
+            limit = max(limit, edns.udp as usize);
+        }
+        let truncated = self.truncate_to(limit);
+        // Vector never overflows, so this may only be `CountOverflow`.
+        // The packet is still valid without OPT record, but incomplete.
+        if self.write_edns().is_err() || self.pos() > limit {
+            Header::set_truncated(&mut self.buf[..12]);
+            Err(self.buf)
+        } else if truncated.answers > 0 || truncated.nameservers > 0 {
+            Err(self.buf)
+        } else {
+            Ok(self.buf)
+        }
+    }
+}
+
+impl<B: Buffer> Builder<B> {
+    /// Creates a new query written into `buf`
+    ///
+    /// Anything already in the buffer is discarded. Returns an error if
+    /// the buffer can't fit the header.
+    pub fn query_in(buf: B, id: u16, recursion: bool)
+        -> Result<Builder<B>, BuildError>
+    {
         let head = Header {
             id: id,
             query: true,
@@ -34,74 +352,739 @@ impl Builder {
             nameservers: 0,
             additional: 0,
         };
-        buf.extend([0u8; 12].iter());
-        head.write(&mut buf[..12]);
-        Builder { buf: buf }
+        Builder::with_header(buf, &head)
+    }
+    /// Creates a response to the query written into `buf`
+    ///
+    /// Same as `new_response` otherwise. Anything already in the buffer is
+    /// discarded. Returns an error if the buffer can't fit the header and
+    /// the questions.
+    pub fn response_in(buf: B, query: &Packet)
+        -> Result<Builder<B>, BuildError>
+    {
+        let head = Header {
+            id: query.header.id,
+            query: false,
+            opcode: query.header.opcode,
+            authoritative: false,
+            truncated: false,
+            recursion_desired: query.header.recursion_desired,
+            recursion_available: false,
+            authenticated_data: false,
+            checking_disabled: query.header.checking_disabled,
+            response_code: ResponseCode::NoError,
+            questions: 0,
+            answers: 0,
+            nameservers: 0,
+            additional: 0,
+        };
+        let mut builder = Builder::with_header(buf, &head)?;
+        builder.edns = query.opt.as_ref().map(Edns::echo);
+        for q in &query.questions {
+            builder.copy_question(q)?;
+        }
+        Ok(builder)
+    }
+    fn copy_question(&mut self, q: &Question) -> Result<(), BuildError> {
+        self.write_question(&q.qname, q.prefer_unicast, q.qtype, q.qclass)
+    }
+    fn write_question(&mut self, qname: &Name, prefer_unicast: bool,
+        qtype: QueryType, qclass: QueryClass)
+        -> Result<(), BuildError>
+    {
+        self.check_count(4)?;
+        self.transaction(|b| {
+            let labels = qname.labels().collect::<Vec<_>>();
+            b.write_name_labels(&labels, b.compress);
+            b.put_u16(qtype.into());
+            let prefer_unicast: u16 = if prefer_unicast { 0x8000 } else { 0 };
+            b.put_u16(u16::from(qclass) | prefer_unicast);
+            Ok(())
+        })?;
+        self.increment_count(4);
+        Ok(())
+    }
+    /// Copies a record into the section which count is at `count_pos`
+    fn copy_record(&mut self, rr: &ResourceRecord, count_pos: usize)
+        -> Result<(), BuildError>
+    {
+        self.check_count(count_pos)?;
+        let start = self.pos();
+        self.transaction(|b| {
+            let labels = rr.name.labels().collect::<Vec<_>>();
+            b.write_name_labels(&labels, b.compress);
+            b.put_u16(rr.data.type_code().into());
+            let multicast_unique: u16 = if rr.multicast_unique { 0x8000 } else { 0 };
+            b.put_u16(u16::from(rr.cls) | multicast_unique);
+            b.put_u32(rr.ttl);
+            let len_pos = b.pos();
+            b.put_u16(0);
+            rr.data.write(b)?;
+            b.finish_rdata(len_pos)
+        })?;
+        self.records.push(start);
+        self.increment_count(count_pos);
+        Ok(())
+    }
+    /// Copies an owned record into the section which count is at
+    /// `count_pos`, record data is written as is
+    fn copy_owned_record(&mut self, rr: &OwnedRecord, count_pos: usize)
+        -> Result<(), BuildError>
+    {
+        self.check_count(count_pos)?;
+        let start = self.pos();
+        self.transaction(|b| {
+            let labels = rr.name.as_name().labels().collect::<Vec<_>>();
+            b.write_name_labels(&labels, b.compress);
+            b.put_u16(rr.data.type_code().into());
+            let multicast_unique: u16 = if rr.multicast_unique { 0x8000 } else { 0 };
+            b.put_u16(u16::from(rr.cls) | multicast_unique);
+            b.put_u32(rr.ttl);
+            let len_pos = b.pos();
+            b.put_u16(0);
+            b.put(rr.data.as_bytes());
+            b.finish_rdata(len_pos)
+        })?;
+        self.records.push(start);
+        self.increment_count(count_pos);
+        Ok(())
+    }
+    /// Checks that one more item fits the count at `pos` of the header
+    fn check_count(&self, pos: usize) -> Result<(), BuildError> {
+        let buf = self.buf.as_bytes();
+        if BigEndian::read_u16(&buf[pos..pos+2]) == 65535 {
+            return Err(BuildError::CountOverflow);
+        }
+        Ok(())
+    }
+    /// Increments the count at `pos`, which must be checked beforehand
+    fn increment_count(&mut self, pos: usize) {
+        let buf = self.buf.as_bytes_mut();
+        let old = BigEndian::read_u16(&buf[pos..pos+2]);
+        BigEndian::write_u16(&mut buf[pos..pos+2], old+1);
+    }
+    /// Sets the authoritative answer flag
+    pub fn set_authoritative(&mut self, value: bool) -> &mut Builder<B> {
+        self.modify_header(|h| h.authoritative = value);
+        self
+    }
+    /// Sets the recursion available flag
+    pub fn set_recursion_available(&mut self, value: bool) -> &mut Builder<B> {
+        self.modify_header(|h| h.recursion_available = value);
+        self
+    }
+    /// Sets the response code
+    pub fn set_response_code(&mut self, code: ResponseCode) -> &mut Builder<B> {
+        self.modify_header(|h| h.response_code = code);
+        self
+    }
+    /// Adds an OPT pseudo-RR (RFC 6891) advertising UDP payload size
+    ///
+    /// The record is written at the end of the additional section by
+    /// `build()`, which also uses the payload size (but at least 512 bytes)
+    /// as the limit for the packet size. Space for the record is reserved
+    /// in the buffer, so EDNS should be enabled before adding records to
+    /// a fixed-size buffer.
+    ///
+    /// Other `set_edns_*` methods and `add_edns_option` enable EDNS
+    /// with the payload size of 512 bytes if it was not enabled yet.
+    pub fn set_edns_udp_payload_size(&mut self, size: u16) -> &mut Builder<B> {
+        self.edns_mut().udp = size;
+        self
+    }
+    /// Sets EDNS version (only version 0 is defined so far)
+    pub fn set_edns_version(&mut self, version: u8) -> &mut Builder<B> {
+        self.edns_mut().version = version;
+        self
+    }
+    /// Sets upper 8 bits of the extended 12-bit response code
+    ///
+    /// Lower 4 bits are in the header, see `set_response_code`.
+    pub fn set_edns_extended_rcode(&mut self, extrcode: u8) -> &mut Builder<B> {
+        self.edns_mut().extrcode = extrcode;
+        self
+    }
+    /// Sets DNSSEC OK flag (RFC 3225)
+    pub fn set_edns_dnssec_ok(&mut self, value: bool) -> &mut Builder<B> {
+        let edns = self.edns_mut();
+        if value {
+            edns.flags |= EDNS_DO_FLAG;
+        } else {
+            edns.flags &= !EDNS_DO_FLAG;
+        }
+        self
+    }
+    /// Appends an EDNS option with the raw option data
+    ///
+    /// # Panics
+    ///
+    /// When options don't fit the 65535 bytes of record data
+    pub fn add_edns_option(&mut self, code: u16, data: &[u8])
+        -> &mut Builder<B>
+    {
+        unwrap_built(self.try_add_edns_option(code, data), "an EDNS option")
+    }
+    /// Appends an EDNS option, returns an error if options are too long
+    pub fn try_add_edns_option(&mut self, code: u16, data: &[u8])
+        -> Result<&mut Builder<B>, BuildError>
+    {
+        let len = self.edns.as_ref().map_or(0, |edns| edns.options.len());
+        if len + 4 + data.len() > 65535 {
+            return Err(BuildError::RecordDataTooLong);
+        }
+        {
+            let edns = self.edns_mut();
+            edns.options.put_u16(code);
+            edns.options.put_u16(data.len() as u16);
+            edns.options.extend(data);
+        }
+        Ok(self)
+    }
+    fn edns_mut(&mut self) -> &mut Edns {
+        self.edns.get_or_insert_with(|| Edns {
+            udp: 512,
+            extrcode: 0,
+            version: 0,
+            flags: 0,
+            options: Vec::new(),
+        })
+    }
+    fn with_header(mut buf: B, head: &Header)
+        -> Result<Builder<B>, BuildError>
+    {
+        buf.truncate(0);
+        if buf.remaining_mut() < 12 {
+            return Err(BuildError::BufferFull);
+        }
+        buf.put_slice(&[0u8; 12]);
+        head.write(&mut buf.as_bytes_mut()[..12]);
+        Ok(Builder {
+            buf: buf,
+            labels: Vec::new(),
+            edns: None,
+            compress: true,
+            full: false,
+            records: Vec::new(),
+        })
+    }
+    fn modify_header<F: FnOnce(&mut Header)>(&mut self, f: F) {
+        let head = &mut self.buf.as_bytes_mut()[..12];
+        let mut parsed = Header::parse(head)
+            .expect("builder always writes a valid header");
+        f(&mut parsed);
+        parsed.write(head);
     }
     /// Adds a question to the packet
     ///
+    /// The name is in presentation format as accepted by
+    /// `OwnedName::from_str`, i.e. it may contain escapes.
+    ///
     /// # Panics
     ///
     /// * Answers, nameservers or additional section has already been written
     /// * There are already 65535 questions in the buffer.
     /// * When name is invalid
+    /// * When the question doesn't fit into the buffer
     pub fn add_question(&mut self, qname: &str, prefer_unicast: bool,
         qtype: QueryType, qclass: QueryClass)
-        -> &mut Builder
+        -> &mut Builder<B>
     {
-        if &self.buf[6..12] != b"\x00\x00\x00\x00\x00\x00" {
-            panic!("Too late to add a question");
+        unwrap_built(self.try_add_question(qname, prefer_unicast, qtype, qclass), "a question")
+    }
+    /// Adds a question to the packet, returning an error instead of
+    /// panicking
+    ///
+    /// The buffer is left intact when an error is returned.
+    pub fn try_add_question(&mut self, qname: &str, prefer_unicast: bool,
+        qtype: QueryType, qclass: QueryClass)
+        -> Result<&mut Builder<B>, BuildError>
+    {
+        let qname = parse_name(qname)?;
+        self.try_add_question_name(&qname.as_name(), prefer_unicast,
+                                   qtype, qclass)
+    }
+    /// Adds a question with already parsed name, e.g. taken from
+    /// another packet or an `OwnedName`
+    ///
+    /// # Panics
+    ///
+    /// Same as `add_question`, except the name is always valid
+    pub fn add_question_name(&mut self, qname: &Name, prefer_unicast: bool,
+        qtype: QueryType, qclass: QueryClass)
+        -> &mut Builder<B>
+    {
+        unwrap_built(self.try_add_question_name(qname, prefer_unicast,
+                                                qtype, qclass), "a question")
+    }
+    /// Adds a question with already parsed name, returning an error
+    /// instead of panicking
+    pub fn try_add_question_name(&mut self, qname: &Name,
+        prefer_unicast: bool, qtype: QueryType, qclass: QueryClass)
+        -> Result<&mut Builder<B>, BuildError>
+    {
+        if &self.buf.as_bytes()[6..12] != b"\x00\x00\x00\x00\x00\x00" {
+            return Err(BuildError::SectionOrder);
         }
-        self.write_name(qname);
-        self.buf.write_u16::<BigEndian>(qtype as u16).unwrap();
-        let prefer_unicast: u16 = if prefer_unicast { 0x8000 } else { 0x0000 };
-        self.buf.write_u16::<BigEndian>(qclass as u16 | prefer_unicast).unwrap();
-        let oldq = BigEndian::read_u16(&self.buf[4..6]);
-        if oldq == 65535 {
-            panic!("Too many questions");
+        self.write_question(qname, prefer_unicast, qtype, qclass)?;
+        Ok(self)
+    }
+    /// Adds a resource record to the answers section
+    ///
+    /// # Panics
+    ///
+    /// * Nameservers or additional section has already been written
+    /// * There are already 65535 answers in the buffer.
+    /// * When name or record data is invalid
+    /// * When the record doesn't fit into the buffer
+    pub fn add_answer(&mut self, name: &str, cls: Class, ttl: u32,
+        data: &RecordData)
+        -> &mut Builder<B>
+    {
+        unwrap_built(self.try_add_answer(name, cls, ttl, data), "an answer")
+    }
+    /// Adds a resource record to the answers section, returning an error
+    /// instead of panicking
+    ///
+    /// The buffer is left intact when an error is returned.
+    pub fn try_add_answer(&mut self, name: &str, cls: Class, ttl: u32,
+        data: &RecordData)
+        -> Result<&mut Builder<B>, BuildError>
+    {
+        if &self.buf.as_bytes()[8..12] != b"\x00\x00\x00\x00" {
+            return Err(BuildError::SectionOrder);
         }
-        BigEndian::write_u16(&mut self.buf[4..6], oldq+1);
-        self
+        self.write_record(name, cls, ttl, data, 6)?;
+        Ok(self)
     }
-    fn write_name(&mut self, name: &str) {
-        for part in name.split('.') {
-            assert!(part.len() < 63);
-            let ln = part.len() as u8;
-            self.buf.push(ln);
-            self.buf.extend(part.as_bytes());
+    /// Adds an owned record, e.g. copied from another packet, to
+    /// the answers section
+    ///
+    /// Names in the record data are not compressed.
+    ///
+    /// # Panics
+    ///
+    /// Same as `add_answer`
+    pub fn add_answer_record(&mut self, rr: &OwnedRecord) -> &mut Builder<B> {
+        unwrap_built(self.try_add_answer_record(rr), "an answer")
+    }
+    /// Adds an owned record to the answers section, returning an error
+    /// instead of panicking
+    pub fn try_add_answer_record(&mut self, rr: &OwnedRecord)
+        -> Result<&mut Builder<B>, BuildError>
+    {
+        if &self.buf.as_bytes()[8..12] != b"\x00\x00\x00\x00" {
+            return Err(BuildError::SectionOrder);
         }
-        self.buf.push(0);
+        self.copy_owned_record(rr, 6)?;
+        Ok(self)
     }
-    /// Returns the final packet
+    /// Adds a resource record to the nameservers (authority) section
     ///
-    /// When packet is not truncated method returns `Ok(packet)`. If
-    /// packet is truncated the method returns `Err(packet)`. In both
-    /// cases the packet is fully valid.
+    /// # Panics
     ///
-    /// In the server implementation you may use
-    /// `x.build().unwrap_or_else(|x| x)`.
+    /// * Additional section has already been written
+    /// * There are already 65535 nameservers in the buffer.
+    /// * When name or record data is invalid
+    /// * When the record doesn't fit into the buffer
+    pub fn add_nameserver(&mut self, name: &str, cls: Class, ttl: u32,
+        data: &RecordData)
+        -> &mut Builder<B>
+    {
+        unwrap_built(self.try_add_nameserver(name, cls, ttl, data), "a nameserver")
+    }
+    /// Adds a resource record to the nameservers section, returning an error
+    /// instead of panicking
     ///
-    /// In the client implementation it's probably unwise to send truncated
-    /// packet, as it doesn't make sense. Even panicking may be more
-    /// appropriate.
-    // TODO(tailhook) does the truncation make sense for TCP, and how
-    // to treat it for EDNS0?
-    pub fn build(mut self) -> Result<Vec<u8>,Vec<u8>> {
-        // TODO(tailhook) optimize labels
-        if self.buf.len() > 512 {
-            Header::set_truncated(&mut self.buf[..12]);
-            Err(self.buf)
-        } else {
-            Ok(self.buf)
+    /// The buffer is left intact when an error is returned.
+    pub fn try_add_nameserver(&mut self, name: &str, cls: Class, ttl: u32,
+        data: &RecordData)
+        -> Result<&mut Builder<B>, BuildError>
+    {
+        if &self.buf.as_bytes()[10..12] != b"\x00\x00" {
+            return Err(BuildError::SectionOrder);
         }
+        self.write_record(name, cls, ttl, data, 8)?;
+        Ok(self)
+    }
+    /// Adds an owned record to the nameservers section
+    ///
+    /// # Panics
+    ///
+    /// Same as `add_nameserver`
+    pub fn add_nameserver_record(&mut self, rr: &OwnedRecord)
+        -> &mut Builder<B>
+    {
+        unwrap_built(self.try_add_nameserver_record(rr), "a nameserver")
+    }
+    /// Adds an owned record to the nameservers section, returning
+    /// an error instead of panicking
+    pub fn try_add_nameserver_record(&mut self, rr: &OwnedRecord)
+        -> Result<&mut Builder<B>, BuildError>
+    {
+        if &self.buf.as_bytes()[10..12] != b"\x00\x00" {
+            return Err(BuildError::SectionOrder);
+        }
+        self.copy_owned_record(rr, 8)?;
+        Ok(self)
+    }
+    /// Adds a resource record to the additional section
+    ///
+    /// # Panics
+    ///
+    /// * There are already 65535 additional records in the buffer.
+    /// * When name or record data is invalid
+    /// * When the record doesn't fit into the buffer
+    pub fn add_additional(&mut self, name: &str, cls: Class, ttl: u32,
+        data: &RecordData)
+        -> &mut Builder<B>
+    {
+        unwrap_built(self.try_add_additional(name, cls, ttl, data), "an additional record")
+    }
+    /// Adds a resource record to the additional section, returning an error
+    /// instead of panicking
+    ///
+    /// The buffer is left intact when an error is returned.
+    pub fn try_add_additional(&mut self, name: &str, cls: Class, ttl: u32,
+        data: &RecordData)
+        -> Result<&mut Builder<B>, BuildError>
+    {
+        self.write_record(name, cls, ttl, data, 10)?;
+        Ok(self)
+    }
+    /// Adds an owned record to the additional section
+    ///
+    /// # Panics
+    ///
+    /// Same as `add_additional`
+    pub fn add_additional_record(&mut self, rr: &OwnedRecord)
+        -> &mut Builder<B>
+    {
+        unwrap_built(self.try_add_additional_record(rr),
+                     "an additional record")
+    }
+    /// Adds an owned record to the additional section, returning
+    /// an error instead of panicking
+    pub fn try_add_additional_record(&mut self, rr: &OwnedRecord)
+        -> Result<&mut Builder<B>, BuildError>
+    {
+        self.copy_owned_record(rr, 10)?;
+        Ok(self)
+    }
+    /// Runs `f` which writes a single question or record
+    ///
+    /// If `f` fails or anything didn't fit, everything written by `f`
+    /// is discarded.
+    fn transaction<F>(&mut self, f: F) -> Result<(), BuildError>
+        where F: FnOnce(&mut Self) -> Result<(), BuildError>
+    {
+        let pos = self.pos();
+        let labels = self.labels.len();
+        let mut result = f(self);
+        if result.is_ok() && self.full {
+            result = Err(BuildError::BufferFull);
+        }
+        if result.is_err() {
+            self.full = false;
+            self.buf.truncate(pos);
+            self.labels.truncate(labels);
+        }
+        result
+    }
+    /// Writes a record into the section which count is at `count_pos`
+    fn write_record(&mut self, name: &str, cls: Class, ttl: u32,
+        data: &RecordData, count_pos: usize)
+        -> Result<(), BuildError>
+    {
+        self.check_count(count_pos)?;
+        let start = self.pos();
+        self.transaction(|b| {
+            b.write_name(name)?;
+            b.put_u16(data.type_code().into());
+            b.put_u16(cls.into());
+            b.put_u32(ttl);
+            let len_pos = b.pos();
+            b.put_u16(0);
+            b.write_rdata(data)?;
+            b.finish_rdata(len_pos)
+        })?;
+        self.records.push(start);
+        self.increment_count(count_pos);
+        Ok(())
+    }
+    /// Writes length of record data started at `len_pos`
+    fn finish_rdata(&mut self, len_pos: usize) -> Result<(), BuildError> {
+        if self.full {
+            return Ok(());
+        }
+        let rdlen = self.pos() - len_pos - 2;
+        if rdlen > 65535 {
+            return Err(BuildError::RecordDataTooLong);
+        }
+        let buf = self.buf.as_bytes_mut();
+        BigEndian::write_u16(&mut buf[len_pos..len_pos+2], rdlen as u16);
+        Ok(())
+    }
+    fn write_rdata(&mut self, data: &RecordData) -> Result<(), BuildError> {
+        match *data {
+            RecordData::A(ip) => {
+                self.put(&ip.octets());
+            }
+            RecordData::AAAA(ip) => {
+                self.put(&ip.octets());
+            }
+            RecordData::CNAME(name) | RecordData::NS(name)
+            | RecordData::PTR(name) => {
+                self.write_name(name)?;
+            }
+            RecordData::MX { preference, exchange } => {
+                self.put_u16(preference);
+                self.write_name(exchange)?;
+            }
+            RecordData::SOA { primary_ns, mailbox, serial, refresh, retry,
+                              expire, minimum_ttl } => {
+                self.write_name(primary_ns)?;
+                self.write_name(mailbox)?;
+                self.put_u32(serial);
+                self.put_u32(refresh);
+                self.put_u32(retry);
+                self.put_u32(expire);
+                self.put_u32(minimum_ttl);
+            }
+            RecordData::SRV { priority, weight, port, target } => {
+                self.put_u16(priority);
+                self.put_u16(weight);
+                self.put_u16(port);
+                // RFC 2782 forbids compression of the target name
+                self.write_uncompressed_name(target)?;
+            }
+            RecordData::TXT(strings) => {
+                if strings.is_empty() {
+                    self.put(&[0]);
+                }
+                for chunk in strings {
+                    self.put_character_string(chunk)?;
+                }
+            }
+            RecordData::Raw(_, data) => {
+                self.put(data);
+            }
+        }
+        Ok(())
+    }
+    fn write_name(&mut self, name: &str) -> Result<(), BuildError> {
+        let name = parse_name(name)?;
+        let parts = name.as_name().labels().collect::<Vec<_>>();
+        self.write_name_labels(&parts, self.compress);
+        Ok(())
+    }
+    fn write_uncompressed_name(&mut self, name: &str)
+        -> Result<(), BuildError>
+    {
+        let name = parse_name(name)?;
+        let parts = name.as_name().labels().collect::<Vec<_>>();
+        self.write_name_labels(&parts, false);
+        Ok(())
+    }
+    /// Writes a name consisting of `parts`, not including the root label
+    fn write_name_labels(&mut self, parts: &[&[u8]], compress: bool) {
+        // Labels of a name interrupted by a full buffer can't be searched
+        if compress && !self.full {
+            for idx in 0..parts.len() {
+                if let Some(off) = self.find_suffix(&parts[idx..]) {
+                    self.write_labels(&parts[..idx]);
+                    self.put_u16(0xC000 | off);
+                    return;
+                }
+            }
+        }
+        self.write_labels(parts);
+        self.put(&[0]);
+    }
+    fn write_labels(&mut self, parts: &[&[u8]]) {
+        for part in parts {
+            assert!(part.len() <= 63);
+            let pos = self.pos();
+            self.put(&[part.len() as u8]);
+            self.put(part);
+            // Pointers can only address first 16 KiB of the packet
+            if !self.full && pos < 0x4000 {
+                self.labels.push(pos as u16);
+            }
+        }
+    }
+    /// Finds an offset of previously written name equal to `parts`
+    fn find_suffix(&self, parts: &[&[u8]]) -> Option<u16> {
+        self.labels.iter().cloned()
+            .find(|&off| self.name_equals(off as usize, parts))
+    }
+    /// Compares name written at offset `pos` of the buffer with `parts`
+    fn name_equals(&self, mut pos: usize, parts: &[&[u8]]) -> bool {
+        let buf = self.buf.as_bytes();
+        let mut parts = parts.iter();
+        loop {
+            let byte = buf[pos];
+            if byte & 0b1100_0000 == 0b1100_0000 {
+                pos = (BigEndian::read_u16(&buf[pos..pos+2])
+                       & !0b1100_0000_0000_0000) as usize;
+                continue;
+            }
+            let end = pos + byte as usize + 1;
+            if byte == 0 {
+                return parts.next().is_none();
+            }
+            match parts.next() {
+                Some(part) if *part == &buf[pos+1..end] => {}
+                _ => return false,
+            }
+            pos = end;
+        }
+    }
+    /// Returns the number of bytes written so far
+    fn pos(&self) -> usize {
+        self.buf.as_bytes().len()
+    }
+    /// Appends `data` unless it, or the OPT record reserved after it,
+    /// doesn't fit into the buffer
+    fn put(&mut self, data: &[u8]) {
+        let reserved = self.edns.as_ref().map_or(0, Edns::record_len);
+        if self.full || data.len() + reserved > self.buf.remaining_mut() {
+            self.full = true;
+            return;
+        }
+        self.buf.put_slice(data);
+    }
+    /// Writes the OPT record, if EDNS is enabled
+    fn write_edns(&mut self) -> Result<(), BuildError> {
+        let edns = match self.edns.take() {
+            Some(edns) => edns,
+            None => return Ok(()),
+        };
+        self.check_count(10)?;
+        self.transaction(|b| {
+            b.put(&[0]);  // root name
+            b.put_u16(opt::Record::TYPE as u16);
+            b.put_u16(edns.udp);
+            b.put(&[edns.extrcode, edns.version]);
+            b.put_u16(edns.flags);
+            b.put_u16(edns.options.len() as u16);
+            b.put(&edns.options);
+            Ok(())
+        })?;
+        self.increment_count(10);
+        Ok(())
+    }
+    /// Drops whole records from the end until the packet fits `limit`
+    ///
+    /// Space for the OPT record is accounted for, and the record itself
+    /// is never dropped, nor are questions. So the packet may still be
+    /// larger than `limit` if they don't fit.
+    ///
+    /// TC flag is set if any answer or authority record was dropped, but
+    /// not if only additional records were (RFC 2181 Section 9).
+    pub fn truncate_to(&mut self, limit: usize) -> Truncated {
+        let opt_len = self.edns.as_ref().map_or(0, Edns::record_len);
+        let mut truncated = Truncated::default();
+        while self.pos() + opt_len > limit {
+            let start = match self.records.pop() {
+                Some(start) => start,
+                None => break,
+            };
+            self.buf.truncate(start);
+            // The last record belongs to the last non-empty section
+            let head = self.buf.as_bytes_mut();
+            let (pos, counter) = if &head[10..12] != b"\x00\x00" {
+                (10, &mut truncated.additional)
+            } else if &head[8..10] != b"\x00\x00" {
+                (8, &mut truncated.nameservers)
+            } else {
+                (6, &mut truncated.answers)
+            };
+            let old = BigEndian::read_u16(&head[pos..pos+2]);
+            BigEndian::write_u16(&mut head[pos..pos+2], old-1);
+            *counter += 1;
+        }
+        let len = self.pos();
+        self.labels.retain(|&off| (off as usize) < len);
+        if truncated.answers > 0 || truncated.nameservers > 0 {
+            Header::set_truncated(&mut self.buf.as_bytes_mut()[..12]);
+        }
+        truncated
+    }
+    /// Returns the buffer with the final packet
+    ///
+    /// Unlike `build()`, no size limit is applied except the buffer size
+    /// itself. Returns an error if the OPT record doesn't fit, which
+    /// may only happen if EDNS was enabled after adding records.
+    pub fn finish(mut self) -> Result<B, BuildError> {
+        self.write_edns()?;
+        Ok(self.buf)
+    }
+}
+
+fn parse_name(name: &str) -> Result<OwnedName, BuildError> {
+    name.parse().map_err(|e| match e {
+        Error::NameTooLong => BuildError::NameTooLong,
+        Error::LabelTooLong => BuildError::LabelTooLong,
+        _ => BuildError::InvalidName,
+    })
+}
+
+/// Panics on error with the message of the panicking builder methods
+///
+/// `what` is the item being added, like "an answer".
+fn unwrap_built<T>(result: Result<T, BuildError>, what: &str) -> T {
+    match result {
+        Ok(value) => value,
+        Err(BuildError::SectionOrder) => panic!("Too late to add {}", what),
+        Err(e @ BuildError::NameTooLong) | Err(e @ BuildError::LabelTooLong)
+        | Err(e @ BuildError::InvalidName) => panic!("Invalid name: {}", e),
+        Err(e) => panic!("Can't add {}: {}", what, e),
+    }
+}
+
+impl<B: Buffer> Writer for Builder<B> {
+    fn put_bytes(&mut self, data: &[u8]) {
+        self.put(data);
+    }
+    fn put_name(&mut self, name: &Name, compress: bool) {
+        let labels = name.labels().collect::<Vec<_>>();
+        let compress = compress && self.compress;
+        self.write_name_labels(&labels, compress);
+    }
+}
+
+impl<'a> Packet<'a> {
+    /// Serializes the packet, appending it to the buffer
+    ///
+    /// All sections and the OPT record are written, counts in the header
+    /// are set from the actual number of records. When `compress` is
+    /// true, names are compressed where allowed by RFC 3597, otherwise
+    /// compression pointers are never used.
+    ///
+    /// # Panics
+    ///
+    /// * When record data is longer than 65535 bytes after decompression
+    /// * When manually constructed record data contains a character string
+    ///   longer than 255 bytes
+    pub fn write_to(&self, buf: &mut Vec<u8>, compress: bool) {
+        buf.extend(Builder::copy_packet(self, compress).finish()
+            .expect("vector never overflows"));
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use quickcheck::{Arbitrary, Gen, QuickCheck};
+
     use QueryType as QT;
     use QueryClass as QC;
-    use super::Builder;
+    use Class as C;
+    use {ResponseCode, Type};
+    use {Packet, RData};
+    use BuildError;
+    use super::{Builder, RecordData, SliceBuffer, Truncated};
 
     #[test]
     fn build_query() {
@@ -129,4 +1112,665 @@ mod test {
             \x0c_xmpp-server\x04_tcp\x05gmail\x03com\x00\x00!\x00\x01";
         assert_eq!(&bld.build().unwrap()[..], &result[..]);
     }
+
+    #[test]
+    fn build_answer() {
+        let mut bld = Builder::new_query(1573, true);
+        bld.add_question("example.com", false, QT::A, QC::IN);
+        bld.add_answer("example.com", C::IN, 1272,
+            &RecordData::A(Ipv4Addr::new(93, 184, 216, 34)));
+        let result = b"\x06%\x01\x00\x00\x01\x00\x01\x00\x00\x00\x00\
+                      \x07example\x03com\x00\x00\x01\x00\x01\
+                      \xc0\x0c\x00\x01\x00\x01\x00\x00\x04\xf8\
+                      \x00\x04]\xb8\xd8\"";
+        assert_eq!(&bld.build().unwrap()[..], &result[..]);
+    }
+
+    #[test]
+    fn build_all_sections() {
+        let mut bld = Builder::new_query(19184, true);
+        bld.add_question("www.skype.com", false, QT::A, QC::IN);
+        bld.add_answer("www.skype.com", C::IN, 3600,
+            &RecordData::CNAME("livecms.trafficmanager.net"));
+        bld.add_nameserver("net", C::IN, 120275,
+            &RecordData::NS("g.gtld-servers.net"));
+        bld.add_nameserver("skype.com", C::IN, 10800,
+            &RecordData::SOA {
+                primary_ns: "ns1.skype.com",
+                mailbox: "admin.skype.com",
+                serial: 2012031603,
+                refresh: 20864,
+                retry: 3600,
+                expire: 14976,
+                minimum_ttl: 10800,
+            });
+        bld.add_additional("a.gtld-servers.net", C::IN, 41756,
+            &RecordData::A(Ipv4Addr::new(192, 5, 6, 30)));
+        bld.add_additional("skype.com", C::IN, 300,
+            &RecordData::TXT(&[b"v=spf1", b"-all"]));
+        let data = bld.build().unwrap();
+        let packet = Packet::parse(&data).unwrap();
+        assert_eq!(packet.header.answers, 1);
+        assert_eq!(packet.header.nameservers, 2);
+        assert_eq!(packet.header.additional, 2);
+        match packet.answers[0].data {
+            RData::CNAME(cname) => {
+                assert_eq!(&cname.0.to_string()[..],
+                    "livecms.trafficmanager.net");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+        assert_eq!(&packet.nameservers[0].name.to_string()[..], "net");
+        assert_eq!(packet.nameservers[0].ttl, 120275);
+        match packet.nameservers[0].data {
+            RData::NS(ns) => {
+                assert_eq!(&ns.0.to_string()[..], "g.gtld-servers.net");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+        match packet.nameservers[1].data {
+            RData::SOA(ref soa) => {
+                assert_eq!(&soa.primary_ns.to_string()[..], "ns1.skype.com");
+                assert_eq!(&soa.mailbox.to_string()[..], "admin.skype.com");
+                assert_eq!(soa.serial, 2012031603);
+                assert_eq!(soa.minimum_ttl, 10800);
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+        match packet.additional[0].data {
+            RData::A(addr) => {
+                assert_eq!(addr.0, Ipv4Addr::new(192, 5, 6, 30));
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+        match packet.additional[1].data {
+            RData::TXT(ref text) => {
+                assert_eq!(text.iter().collect::<Vec<_>>(),
+                    [&b"v=spf1"[..], &b"-all"[..]]);
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+
+    #[test]
+    fn compress_names() {
+        let mut bld = Builder::new_query(19184, true);
+        bld.add_question("www.skype.com", false, QT::A, QC::IN);
+        bld.add_answer("www.skype.com", C::IN, 3600,
+            &RecordData::CNAME("livecms.trafficmanager.net"));
+        bld.add_nameserver("net", C::IN, 120275,
+            &RecordData::NS("g.gtld-servers.net"));
+        bld.add_additional("a.gtld-servers.net", C::IN, 41756,
+            &RecordData::A(Ipv4Addr::new(192, 5, 6, 30)));
+        let result = b"\x4a\xf0\x01\x00\x00\x01\x00\x01\x00\x01\x00\x01\
+                       \x03www\x05skype\x03com\x00\x00\x01\x00\x01\
+                       \xc0\x0c\x00\x05\x00\x01\x00\x00\x0e\x10\
+                       \x00\x1c\x07livecms\x0etrafficmanager\x03net\x00\
+                       \xc0\x42\x00\x02\x00\x01\x00\x01\xd5\xd3\x00\x11\
+                       \x01g\x0cgtld-servers\xc0\x42\
+                       \x01a\xc0\x55\x00\x01\x00\x01\x00\x00\xa3\x1c\
+                       \x00\x04\xc0\x05\x06\x1e";
+        assert_eq!(&bld.build().unwrap()[..], &result[..]);
+    }
+
+    #[test]
+    fn no_compression_for_srv_target() {
+        let mut bld = Builder::new_query(1, true);
+        bld.add_question("_sip._udp.example.com", false, QT::SRV, QC::IN);
+        bld.add_answer("_sip._udp.example.com", C::IN, 60,
+            &RecordData::SRV {
+                priority: 1,
+                weight: 2,
+                port: 5060,
+                target: "example.com",
+            });
+        let data = bld.build().unwrap();
+        assert_eq!(&data[data.len()-13..], b"\x07example\x03com\x00");
+        let packet = Packet::parse(&data).unwrap();
+        match packet.answers[0].data {
+            RData::SRV(srv) => {
+                assert_eq!(&srv.target.to_string()[..], "example.com");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+
+    #[test]
+    fn many_records_fit() {
+        let mut bld = Builder::new_query(1, true);
+        bld.add_question("some-long-host-name.example.com", false,
+                         QT::A, QC::IN);
+        for i in 0..25 {
+            bld.add_answer("some-long-host-name.example.com", C::IN, 60,
+                &RecordData::A(Ipv4Addr::new(10, 0, 0, i)));
+        }
+        let data = bld.build().unwrap();
+        assert_eq!(data.len(), 12 + 37 + 25*16);
+        let packet = Packet::parse(&data).unwrap();
+        assert_eq!(packet.answers.len(), 25);
+        for ans in &packet.answers {
+            assert_eq!(&ans.name.to_string()[..],
+                "some-long-host-name.example.com");
+        }
+    }
+
+    #[test]
+    fn build_response() {
+        let query = b"\x95\xce\x01\x00\x00\x01\x00\x00\x00\x00\x00\x01\
+            \x06google\x03com\x00\x00\x01\x00\
+            \x01\x00\x00\x29\x10\x00\x00\x00\x80\x00\x00\x00";
+        let query = Packet::parse(query).unwrap();
+        let mut bld = Builder::new_response(&query);
+        bld.set_authoritative(true);
+        bld.set_recursion_available(true);
+        bld.add_answer("google.com", C::IN, 239,
+            &RecordData::A(Ipv4Addr::new(64, 233, 164, 100)));
+        let result = b"\x95\xce\x85\x80\x00\x01\x00\x01\x00\x00\x00\x01\
+            \x06google\x03com\x00\x00\x01\x00\x01\
+            \xc0\x0c\x00\x01\x00\x01\x00\x00\x00\xef\x00\x04@\xe9\xa4d\
+            \x00\x00\x29\x10\x00\x00\x00\x80\x00\x00\x00";
+        assert_eq!(&bld.build().unwrap()[..], &result[..]);
+    }
+
+    #[test]
+    fn build_error_response() {
+        let query = b"[\xd9\x01\x00\x00\x01\x00\x00\x00\x00\x00\x00\
+            \x0c_xmpp-server\x04_tcp\x05gmail\x03com\x00\x00!\x00\x01";
+        let query = Packet::parse(query).unwrap();
+        let mut bld = Builder::new_response(&query);
+        bld.set_response_code(ResponseCode::NameError);
+        let data = bld.build().unwrap();
+        let packet = Packet::parse(&data).unwrap();
+        assert_eq!(packet.header.id, 23513);
+        assert!(!packet.header.query);
+        assert!(packet.header.recursion_desired);
+        assert_eq!(packet.header.response_code, ResponseCode::NameError);
+        assert_eq!(packet.questions.len(), 1);
+        assert_eq!(packet.questions[0].qtype, QT::SRV);
+        assert_eq!(&packet.questions[0].qname.to_string()[..],
+            "_xmpp-server._tcp.gmail.com");
+        assert!(packet.opt.is_none());
+    }
+
+    #[test]
+    fn build_edns_query() {
+        let mut bld = Builder::new_query(38350, true);
+        bld.add_question("google.com", false, QT::A, QC::IN);
+        bld.set_edns_udp_payload_size(4096);
+        bld.set_edns_dnssec_ok(true);
+        bld.add_edns_option(10, b"\x01\x02\x03\x04\x05\x06\x07\x08");
+        let result = b"\x95\xce\x01\x00\x00\x01\x00\x00\x00\x00\x00\x01\
+            \x06google\x03com\x00\x00\x01\x00\x01\
+            \x00\x00\x29\x10\x00\x00\x00\x80\x00\x00\x0c\
+            \x00\x0a\x00\x08\x01\x02\x03\x04\x05\x06\x07\x08";
+        assert_eq!(&bld.build().unwrap()[..], &result[..]);
+    }
+
+    #[test]
+    fn edns_extended_rcode() {
+        let mut bld = Builder::new_query(1, true);
+        bld.set_edns_extended_rcode(1);  // BADVERS
+        bld.set_edns_version(0);
+        let data = bld.build().unwrap();
+        let packet = Packet::parse(&data).unwrap();
+        let opt = packet.opt.unwrap();
+        assert_eq!(opt.udp, 512);
+        assert_eq!(opt.extrcode, 1);
+        assert_eq!(opt.version, 0);
+        assert_eq!(opt.flags, 0);
+    }
+
+    #[test]
+    fn truncate_by_edns_size() {
+        let mut bld = Builder::new_query(1, true);
+        bld.add_question("example.com", false, QT::TXT, QC::IN);
+        let text: &[&[u8]] = &[&[b'x'; 255], &[b'y'; 255]];
+        bld.add_answer("example.com", C::IN, 60, &RecordData::TXT(text));
+        bld.set_edns_udp_payload_size(1232);
+        assert!(bld.build().is_ok());
+
+        let mut bld = Builder::new_query(1, true);
+        bld.add_question("example.com", false, QT::TXT, QC::IN);
+        bld.add_answer("example.com", C::IN, 60, &RecordData::TXT(text));
+        bld.set_edns_udp_payload_size(512);
+        let data = bld.build().unwrap_err();
+        assert!(data.len() <= 512);
+        let packet = Packet::parse(&data).unwrap();
+        assert!(packet.header.truncated);
+        assert!(packet.header.recursion_desired);
+        assert_eq!(packet.questions.len(), 1);
+        assert_eq!(packet.answers.len(), 0);
+        assert_eq!(packet.opt.unwrap().udp, 512);
+    }
+
+    #[test]
+    fn questions_exceed_limit() {
+        let mut bld = Builder::new_query(1, true);
+        for i in 0..3 {
+            let name = format!("{}{}.example.com", "x".repeat(60), i);
+            let name = format!("{0}.{0}.{0}", name);
+            bld.add_question(&name, false, QT::A, QC::IN);
+        }
+        let data = bld.build().unwrap_err();
+        assert!(data.len() > 512);
+        let packet = Packet::parse(&data).unwrap();
+        assert!(packet.header.truncated);
+        assert_eq!(packet.questions.len(), 3);
+    }
+
+    fn build_sections() -> Builder {
+        let mut bld = Builder::new_query(1, true);
+        bld.add_question("example.com", false, QT::MX, QC::IN);
+        for i in 0..10 {
+            bld.add_answer("example.com", C::IN, 60, &RecordData::MX {
+                preference: i,
+                exchange: &format!("mx{}.example.com", i),
+            });
+        }
+        bld.add_nameserver("example.com", C::IN, 60,
+            &RecordData::NS("ns.example.com"));
+        for i in 0..10 {
+            bld.add_additional(&format!("mx{}.example.com", i), C::IN, 60,
+                &RecordData::AAAA(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, i)));
+        }
+        bld
+    }
+
+    #[test]
+    fn truncate_sections() {
+        let full = build_sections().finish().unwrap();
+        assert_eq!(full.len(), 526);
+
+        // dropping additional records doesn't set TC
+        let data = build_sections().build().unwrap();
+        let packet = Packet::parse(&data).unwrap();
+        assert!(!packet.header.truncated);
+        assert_eq!(data.len(), 498);
+        assert_eq!(packet.additional.len(), 9);
+
+        let mut bld = build_sections();
+        assert_eq!(bld.truncate_to(200), Truncated {
+            answers: 2,
+            nameservers: 1,
+            additional: 10,
+        });
+        let data = bld.finish().unwrap();
+        assert!(data.len() <= 200);
+        let packet = Packet::parse(&data).unwrap();
+        assert!(packet.header.truncated);
+        assert_eq!(packet.answers.len(), 8);
+        assert_eq!(packet.nameservers.len(), 0);
+        match packet.answers[7].data {
+            RData::MX(mx) => {
+                assert_eq!(mx.exchange.to_string(), "mx7.example.com");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+
+        let mut bld = build_sections();
+        assert_eq!(bld.truncate_to(65535), Truncated::default());
+        assert_eq!(bld.finish().unwrap(), full);
+    }
+
+    #[test]
+    fn build_escaped_and_long_labels() {
+        let long = "x".repeat(63);
+        let mut bld = Builder::new_query(1, true);
+        bld.add_question(&format!("{}.a\\.b.example.com.", long), false,
+                         QT::A, QC::IN);
+        let data = bld.build().unwrap();
+        assert_eq!(data[12], 63);
+        assert_eq!(&data[12+64..12+64+17], b"\x03a.b\x07example\x03com\x00");
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid name")]
+    fn build_too_long_label() {
+        let mut bld = Builder::new_query(1, true);
+        bld.add_question(&"x".repeat(64), false, QT::A, QC::IN);
+    }
+
+    #[test]
+    #[should_panic(expected = "Too late to add an answer")]
+    fn answer_after_nameserver() {
+        let mut bld = Builder::new_query(1, true);
+        bld.add_nameserver("net", C::IN, 60, &RecordData::NS("a.net"));
+        bld.add_answer("a.net", C::IN, 60,
+            &RecordData::A(Ipv4Addr::new(127, 0, 0, 1)));
+    }
+
+    #[test]
+    fn build_in_slice() {
+        let mut vec_bld = Builder::new_query(1573, true);
+        vec_bld.add_question("example.com", false, QT::A, QC::IN);
+        let mut data = [0u8; 512];
+        let mut bld = Builder::query_in(SliceBuffer::new(&mut data), 1573, true)
+            .unwrap();
+        bld.try_add_question("example.com", false, QT::A, QC::IN).unwrap();
+        assert_eq!(bld.finish().unwrap().into_bytes(),
+                   &vec_bld.build().unwrap()[..]);
+
+        let mut data = [0u8; 11];
+        assert_matches!(Builder::query_in(SliceBuffer::new(&mut data), 1, true),
+                        Err(BuildError::BufferFull));
+    }
+
+    #[test]
+    fn slice_full() {
+        let mut data = [0u8; 110];
+        let mut bld = Builder::query_in(SliceBuffer::new(&mut data), 1, true)
+            .unwrap();
+        bld.set_edns_udp_payload_size(1232);
+        bld.add_question("sub.example.com", false, QT::A, QC::IN);
+        // 33 bytes so far, 11 are reserved for OPT, each answer is 16 bytes
+        for _ in 0..3 {
+            bld.try_add_answer("sub.example.com", C::IN, 60,
+                &RecordData::A(Ipv4Addr::new(127, 0, 0, 1))).unwrap();
+        }
+        // labels of the discarded name must not be used for compression
+        assert_eq!(bld.try_add_answer("some-long-name.example.com", C::IN, 60,
+            &RecordData::A(Ipv4Addr::new(127, 0, 0, 2))).unwrap_err(),
+            BuildError::BufferFull);
+        assert_eq!(bld.try_add_answer("a.some-long-name.example.com", C::IN, 60,
+            &RecordData::CNAME("some-long-name.example.com")).unwrap_err(),
+            BuildError::BufferFull);
+        bld.try_add_answer("x.example.com", C::IN, 60,
+            &RecordData::A(Ipv4Addr::new(127, 0, 0, 3))).unwrap();
+        let data = bld.finish().unwrap().into_bytes();
+        assert_eq!(data.len(), 110);
+        let packet = Packet::parse(data).unwrap();
+        assert_eq!(packet.answers.len(), 4);
+        assert_eq!(packet.answers[3].name.to_string(), "x.example.com");
+        assert_eq!(packet.opt.unwrap().udp, 1232);
+    }
+
+    #[test]
+    fn try_add_errors() {
+        let mut bld = Builder::new_query(1, true);
+        bld.try_add_question("example.com", false, QT::A, QC::IN).unwrap();
+        assert_eq!(bld.try_add_question(&"x".repeat(64), false, QT::A, QC::IN)
+                   .unwrap_err(), BuildError::LabelTooLong);
+        let long = ["x".repeat(63), "x".repeat(63), "x".repeat(63),
+                    "x".repeat(63)].join(".");
+        assert_eq!(bld.try_add_question(&long, false, QT::A, QC::IN)
+                   .unwrap_err(), BuildError::NameTooLong);
+        assert_eq!(bld.try_add_question("a..b", false, QT::A, QC::IN)
+                   .unwrap_err(), BuildError::InvalidName);
+        bld.try_add_nameserver("example.com", C::IN, 60,
+            &RecordData::NS("ns.example.com")).unwrap();
+        let snapshot = bld.buf.clone();
+        assert_eq!(bld.try_add_answer("example.com", C::IN, 60,
+            &RecordData::A(Ipv4Addr::new(127, 0, 0, 1))).unwrap_err(),
+            BuildError::SectionOrder);
+        assert_eq!(bld.try_add_question("example.com", false, QT::A, QC::IN)
+                   .unwrap_err(), BuildError::SectionOrder);
+        // errors in record data discard the whole record
+        assert_eq!(bld.try_add_additional("ns.example.com", C::IN, 60,
+            &RecordData::MX { preference: 10, exchange: "a\\" })
+            .unwrap_err(), BuildError::InvalidName);
+        let text: &[&[u8]] = &[&[b'x'; 256]];
+        assert_eq!(bld.try_add_additional("ns.example.com", C::IN, 60,
+            &RecordData::TXT(text)).unwrap_err(), BuildError::StringTooLong);
+        let raw = vec![0u8; 65536];
+        assert_eq!(bld.try_add_additional("ns.example.com", C::IN, 60,
+            &RecordData::Raw(Type::NULL, &raw)).unwrap_err(),
+            BuildError::RecordDataTooLong);
+        assert_eq!(bld.buf, snapshot);
+        assert_eq!(bld.try_add_edns_option(65001, &raw[..65532]).unwrap_err(),
+                   BuildError::RecordDataTooLong);
+        let packet = bld.build().unwrap();
+        let packet = Packet::parse(&packet).unwrap();
+        assert!(packet.opt.is_none());
+        assert_eq!(packet.questions.len(), 1);
+        assert_eq!(packet.nameservers.len(), 1);
+        assert_eq!(packet.additional.len(), 0);
+    }
+
+    #[test]
+    fn count_overflow() {
+        let mut bld = Builder::new_query(1, true);
+        for _ in 0..65535 {
+            bld.try_add_additional(".", C::IN, 0, &RecordData::Raw(Type::NULL, b""))
+                .unwrap();
+        }
+        assert_eq!(bld.try_add_additional(".", C::IN, 0,
+            &RecordData::Raw(Type::NULL, b"")).unwrap_err(),
+            BuildError::CountOverflow);
+        let mut bld = Builder::from_packet(&Packet::parse(&bld.finish().unwrap())
+                                           .unwrap());
+        bld.set_edns_udp_payload_size(1232);
+        assert_eq!(bld.finish().unwrap_err(), BuildError::CountOverflow);
+    }
+
+    #[test]
+    fn build_full_additional() {
+        let mut bld = Builder::new_query(1, true);
+        for _ in 0..65535 {
+            bld.add_additional(".", C::IN, 0, &RecordData::Raw(Type::NULL, b""));
+        }
+        bld.set_edns_udp_payload_size(1232);
+        let data = bld.build().unwrap_or_else(|x| x);
+        let packet = Packet::parse(&data).unwrap();
+        assert!(data.len() <= 1232);
+        assert!(!packet.additional.is_empty());
+        assert_eq!(packet.opt.unwrap().udp, 1232);
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit into the buffer")]
+    fn slice_add_panics() {
+        let mut data = [0u8; 20];
+        let mut bld = Builder::query_in(SliceBuffer::new(&mut data), 1, true)
+            .unwrap();
+        bld.add_question("example.com", false, QT::A, QC::IN);
+    }
+
+    #[test]
+    fn copy_packet() {
+        let response = b"\x06%\x81\x80\x00\x01\x00\x01\x00\x00\x00\x01\
+            \x07example\x03com\x00\x00\x0f\x00\x01\
+            \xc0\x0c\x00\x0f\x00\x01\x00\x00\x0e\x10\x00\x09\x00\x0a\
+            \x04mail\xc0\x0c\
+            \x00\x00\x29\x04\xd0\x00\x00\x00\x00\x00\x00";
+        let packet = Packet::parse(response).unwrap();
+        let mut bld = Builder::from_packet(&packet);
+        bld.add_additional("mail.example.com", C::IN, 3600,
+            &RecordData::A(Ipv4Addr::new(192, 0, 2, 25)));
+        let result = b"\x06%\x81\x80\x00\x01\x00\x01\x00\x00\x00\x02\
+            \x07example\x03com\x00\x00\x0f\x00\x01\
+            \xc0\x0c\x00\x0f\x00\x01\x00\x00\x0e\x10\x00\x09\x00\x0a\
+            \x04mail\xc0\x0c\
+            \xc0\x2b\x00\x01\x00\x01\x00\x00\x0e\x10\x00\x04\xc0\x00\x02\x19\
+            \x00\x00\x29\x04\xd0\x00\x00\x00\x00\x00\x00";
+        assert_eq!(&bld.build().unwrap()[..], &result[..]);
+    }
+
+    #[test]
+    fn write_uncompressed() {
+        let response = b"\x06%\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x07example\x03com\x00\x00\x0f\x00\x01\
+            \xc0\x0c\x00\x0f\x00\x01\x00\x00\x0e\x10\x00\x09\x00\x0a\
+            \x04mail\xc0\x0c";
+        let packet = Packet::parse(response).unwrap();
+        let mut buf = Vec::new();
+        packet.write_to(&mut buf, true);
+        assert_eq!(&buf[..], &response[..]);
+        buf.clear();
+        packet.write_to(&mut buf, false);
+        let result = b"\x06%\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x07example\x03com\x00\x00\x0f\x00\x01\
+            \x07example\x03com\x00\x00\x0f\x00\x01\x00\x00\x0e\x10\x00\x14\
+            \x00\x0a\x04mail\x07example\x03com\x00";
+        assert_eq!(&buf[..], &result[..]);
+    }
+
+    #[derive(Debug, Clone)]
+    struct TestRecord {
+        name: String,
+        ttl: u32,
+        data: TestData,
+    }
+
+    #[derive(Debug, Clone)]
+    enum TestData {
+        A(Ipv4Addr),
+        Aaaa(Ipv6Addr),
+        Cname(String),
+        Mx(u16, String),
+        Soa(String, String, u32),
+        Srv(u16, String),
+        Txt(Vec<Vec<u8>>),
+        /// Private use type, parsed as `RData::Unknown`
+        Raw(u16, Vec<u8>),
+    }
+
+    #[derive(Debug, Clone)]
+    struct TestPacket {
+        id: u16,
+        questions: Vec<String>,
+        answers: Vec<TestRecord>,
+        nameservers: Vec<TestRecord>,
+        additional: Vec<TestRecord>,
+        edns: Option<(u16, Vec<u8>)>,
+    }
+
+    fn test_name(g: &mut Gen) -> String {
+        let count = usize::arbitrary(g) % 4;
+        let mut labels = (0..count).map(|_| {
+            if bool::arbitrary(g) {
+                g.choose(&["www", "mail", "example", "com", "net"])
+                    .unwrap().to_string()
+            } else {
+                let len = usize::arbitrary(g) % 63 + 1;
+                (0..len).map(|_| *g.choose(b"abcxyz019-").unwrap() as char)
+                    .collect()
+            }
+        }).collect::<Vec<_>>();
+        if count > 0 && bool::arbitrary(g) {
+            labels.push("example".to_string());
+            labels.push("com".to_string());
+        }
+        labels.join(".")
+    }
+
+    fn test_records(g: &mut Gen) -> Vec<TestRecord> {
+        let count = usize::arbitrary(g) % 4;
+        (0..count).map(|_| TestRecord::arbitrary(g)).collect()
+    }
+
+    impl Arbitrary for TestRecord {
+        fn arbitrary(g: &mut Gen) -> TestRecord {
+            let data = match u8::arbitrary(g) % 8 {
+                0 => TestData::A(Ipv4Addr::from(u32::arbitrary(g))),
+                1 => TestData::Aaaa(Ipv6Addr::from(u128::arbitrary(g))),
+                2 => TestData::Cname(test_name(g)),
+                3 => TestData::Mx(u16::arbitrary(g), test_name(g)),
+                4 => TestData::Soa(test_name(g), test_name(g),
+                                   u32::arbitrary(g)),
+                5 => TestData::Srv(u16::arbitrary(g), test_name(g)),
+                6 => TestData::Txt(Vec::<Vec<u8>>::arbitrary(g)),
+                _ => TestData::Raw(0xFF00 | u16::from(u8::arbitrary(g)),
+                                   Vec::<u8>::arbitrary(g)),
+            };
+            TestRecord {
+                name: test_name(g),
+                ttl: u32::arbitrary(g) & 0x7FFF_FFFF,
+                data,
+            }
+        }
+    }
+
+    impl Arbitrary for TestPacket {
+        fn arbitrary(g: &mut Gen) -> TestPacket {
+            let count = usize::arbitrary(g) % 3;
+            TestPacket {
+                id: u16::arbitrary(g),
+                questions: (0..count).map(|_| test_name(g)).collect(),
+                answers: test_records(g),
+                nameservers: test_records(g),
+                additional: test_records(g),
+                edns: Option::arbitrary(g),
+            }
+        }
+    }
+
+    impl TestPacket {
+        fn build(&self) -> Vec<u8> {
+            let mut bld = Builder::new_query(self.id, true);
+            for q in &self.questions {
+                bld.add_question(q, false, QT::A, QC::IN);
+            }
+            for rr in &self.answers {
+                rr.with_data(|d| { bld.add_answer(&rr.name, C::IN, rr.ttl, d); });
+            }
+            for rr in &self.nameservers {
+                rr.with_data(|d| { bld.add_nameserver(&rr.name, C::IN, rr.ttl, d); });
+            }
+            for rr in &self.additional {
+                rr.with_data(|d| { bld.add_additional(&rr.name, C::IN, rr.ttl, d); });
+            }
+            if let Some((udp, ref data)) = self.edns {
+                bld.set_edns_udp_payload_size(udp);
+                bld.add_edns_option(0xFFFF, data);
+            }
+            bld.build().unwrap_or_else(|x| x)
+        }
+    }
+
+    impl TestRecord {
+        fn with_data<F: FnOnce(&RecordData)>(&self, f: F) {
+            let text;
+            let data = match self.data {
+                TestData::A(ip) => RecordData::A(ip),
+                TestData::Aaaa(ip) => RecordData::AAAA(ip),
+                TestData::Cname(ref name) => RecordData::CNAME(name),
+                TestData::Mx(preference, ref exchange) => {
+                    RecordData::MX { preference, exchange }
+                }
+                TestData::Soa(ref primary_ns, ref mailbox, serial) => {
+                    RecordData::SOA { primary_ns, mailbox, serial,
+                        refresh: 1, retry: 2, expire: 3, minimum_ttl: 4 }
+                }
+                TestData::Srv(port, ref target) => {
+                    RecordData::SRV { priority: 0, weight: 0, port, target }
+                }
+                TestData::Txt(ref strings) => {
+                    text = strings.iter()
+                        .map(|x| &x[..x.len().min(255)])
+                        .collect::<Vec<_>>();
+                    RecordData::TXT(&text)
+                }
+                TestData::Raw(typ, ref data) => {
+                    RecordData::Raw(Type::from(typ), data)
+                }
+            };
+            f(&data)
+        }
+    }
+
+    #[test]
+    fn parse_write_parse_identity() {
+        fn prop(packet: TestPacket) -> bool {
+            let data = packet.build();
+            let parsed = Packet::parse(&data).unwrap();
+            let mut compressed = Vec::new();
+            parsed.write_to(&mut compressed, true);
+            let mut uncompressed = Vec::new();
+            parsed.write_to(&mut uncompressed, false);
+            // Builder compresses the same way as the original packet, and
+            // the uncompressed form is the same packet, so both must
+            // serialize back to the same bytes in either mode
+            let mut recompressed = Vec::new();
+            Packet::parse(&uncompressed).unwrap()
+                .write_to(&mut recompressed, true);
+            let mut decompressed = Vec::new();
+            Packet::parse(&compressed).unwrap()
+                .write_to(&mut decompressed, false);
+            compressed == data &&
+            recompressed == data &&
+            decompressed == uncompressed
+        }
+        QuickCheck::new().tests(500)
+            .quickcheck(prop as fn(TestPacket) -> bool);
+    }
 }
@@ -1,3 +1,5 @@
+use core::fmt;
+
 use {Error};
 use rdata::Record;
 use rdata::*;
@@ -8,43 +10,81 @@ use rdata::*;
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Type {
     /// a host addresss
-    A = a::Record::TYPE,
+    A,
     /// an authoritative name server
-    NS = ns::Record::TYPE,
+    NS,
     /// a mail forwarder (Obsolete - use MX)
-    MF = mf::Record::TYPE,
+    MF,
     /// the canonical name for an alias
-    CNAME = cname::Record::TYPE,
+    CNAME,
     /// marks the start of a zone of authority
-    SOA = soa::Record::TYPE,
+    SOA,
     /// a mailbox domain name (EXPERIMENTAL)
-    MB = mb::Record::TYPE,
+    MB,
     /// a mail group member (EXPERIMENTAL)
-    MG = mg::Record::TYPE,
+    MG,
     /// a mail rename domain name (EXPERIMENTAL)
-    MR = mr::Record::TYPE,
+    MR,
     /// a null RR (EXPERIMENTAL)
-    NULL = null::Record::TYPE,
+    NULL,
     /// a well known service description
-    WKS = wks::Record::TYPE,
+    WKS,
     /// a domain name pointer
-    PTR = ptr::Record::TYPE,
+    PTR,
     /// host information
-    HINFO = hinfo::Record::TYPE,
+    HINFO,
     /// mailbox or mail list information
-    MINFO = minfo::Record::TYPE,
+    MINFO,
     /// mail exchange
-    MX = mx::Record::TYPE,
+    MX,
     /// text strings
-    TXT = txt::Record::TYPE,
+    TXT,
     /// IPv6 host address (RFC 2782)
-    AAAA = aaaa::Record::TYPE,
+    AAAA,
     /// service record (RFC 2782)
-    SRV = srv::Record::TYPE,
+    SRV,
     /// EDNS0 options (RFC 6891)
-    OPT = opt::Record::TYPE,
+    OPT,
     /// next secure record (RFC 4034, RFC 6762)
-    NSEC = nsec::Record::TYPE,
+    NSEC,
+    /// delegation signer (RFC 4034)
+    DS,
+    /// signature of a record set (RFC 4034)
+    RRSIG,
+    /// public key of a zone (RFC 4034)
+    DNSKEY,
+    /// hashed next secure record (RFC 5155)
+    NSEC3,
+    /// parameters of hashed denial of existence (RFC 5155)
+    NSEC3PARAM,
+    /// child copy of DS record (RFC 7344)
+    CDS,
+    /// child copy of DNSKEY record (RFC 7344)
+    CDNSKEY,
+    /// SSH key fingerprint (RFC 4255)
+    SSHFP,
+    /// TLSA certificate association (RFC 6698)
+    TLSA,
+    /// S/MIME certificate association (RFC 8162)
+    SMIMEA,
+    /// OpenPGP public key (RFC 7929)
+    OPENPGPKEY,
+    /// Certification Authority Authorization (RFC 8659)
+    CAA,
+    /// general purpose service binding (RFC 9460)
+    SVCB,
+    /// service binding for HTTPS (RFC 9460)
+    HTTPS,
+    /// location information (RFC 1876)
+    LOC,
+    /// naming authority pointer (RFC 3403)
+    NAPTR,
+    /// delegation name (RFC 6672)
+    DNAME,
+    /// uniform resource identifier (RFC 7553)
+    URI,
+    /// Any type not known to this library (RFC 3597)
+    Unknown(u16),
 }
 
 /// The QTYPE value according to RFC 1035
@@ -54,47 +94,87 @@ pub enum Type {
 #[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
 pub enum QueryType {
     /// a host addresss
-    A = a::Record::TYPE,
+    A,
     /// an authoritative name server
-    NS = ns::Record::TYPE,
+    NS,
     /// a mail forwarder (Obsolete - use MX)
-    MF = mf::Record::TYPE,
+    MF,
     /// the canonical name for an alias
-    CNAME = cname::Record::TYPE,
+    CNAME,
     /// marks the start of a zone of authority
-    SOA = soa::Record::TYPE,
+    SOA,
     /// a mailbox domain name (EXPERIMENTAL)
-    MB = mb::Record::TYPE,
+    MB,
     /// a mail group member (EXPERIMENTAL)
-    MG = mg::Record::TYPE,
+    MG,
     /// a mail rename domain name (EXPERIMENTAL)
-    MR = mr::Record::TYPE,
+    MR,
     /// a null RR (EXPERIMENTAL)
-    NULL = null::Record::TYPE,
+    NULL,
     /// a well known service description
-    WKS = wks::Record::TYPE,
+    WKS,
     /// a domain name pointer
-    PTR = ptr::Record::TYPE,
+    PTR,
     /// host information
-    HINFO = hinfo::Record::TYPE,
+    HINFO,
     /// mailbox or mail list information
-    MINFO = minfo::Record::TYPE,
+    MINFO,
     /// mail exchange
-    MX = mx::Record::TYPE,
+    MX,
     /// text strings
-    TXT = txt::Record::TYPE,
+    TXT,
     /// IPv6 host address (RFC 2782)
-    AAAA = aaaa::Record::TYPE,
+    AAAA,
     /// service record (RFC 2782)
-    SRV = srv::Record::TYPE,
+    SRV,
     /// A request for a transfer of an entire zone
-    AXFR = axfr::Record::TYPE,
+    AXFR,
     /// A request for mailbox-related records (MB, MG or MR)
-    MAILB = mailb::Record::TYPE,
+    MAILB,
     /// A request for mail agent RRs (Obsolete - see MX)
-    MAILA = maila::Record::TYPE,
+    MAILA,
     /// A request for all records
-    All = all::Record::TYPE,
+    All,
+    /// next secure record (RFC 4034, RFC 6762)
+    NSEC,
+    /// delegation signer (RFC 4034)
+    DS,
+    /// signature of a record set (RFC 4034)
+    RRSIG,
+    /// public key of a zone (RFC 4034)
+    DNSKEY,
+    /// hashed next secure record (RFC 5155)
+    NSEC3,
+    /// parameters of hashed denial of existence (RFC 5155)
+    NSEC3PARAM,
+    /// child copy of DS record (RFC 7344)
+    CDS,
+    /// child copy of DNSKEY record (RFC 7344)
+    CDNSKEY,
+    /// SSH key fingerprint (RFC 4255)
+    SSHFP,
+    /// TLSA certificate association (RFC 6698)
+    TLSA,
+    /// S/MIME certificate association (RFC 8162)
+    SMIMEA,
+    /// OpenPGP public key (RFC 7929)
+    OPENPGPKEY,
+    /// Certification Authority Authorization (RFC 8659)
+    CAA,
+    /// general purpose service binding (RFC 9460)
+    SVCB,
+    /// service binding for HTTPS (RFC 9460)
+    HTTPS,
+    /// location information (RFC 1876)
+    LOC,
+    /// naming authority pointer (RFC 3403)
+    NAPTR,
+    /// delegation name (RFC 6672)
+    DNAME,
+    /// uniform resource identifier (RFC 7553)
+    URI,
+    /// Any type not known to this library (RFC 3597)
+    Unknown(u16),
 }
 
 
@@ -102,30 +182,34 @@ pub enum QueryType {
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Class {
     /// the Internet
-    IN = 1,
+    IN,
     /// the CSNET class (Obsolete - used only for examples in some obsolete
     /// RFCs)
-    CS = 2,
+    CS,
     /// the CHAOS class
-    CH = 3,
+    CH,
     /// Hesiod [Dyer 87]
-    HS = 4,
+    HS,
+    /// Any class not known to this library (RFC 3597)
+    Unknown(u16),
 }
 
 /// The QCLASS value according to RFC 1035
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum QueryClass {
     /// the Internet
-    IN = 1,
+    IN,
     /// the CSNET class (Obsolete - used only for examples in some obsolete
     /// RFCs)
-    CS = 2,
+    CS,
     /// the CHAOS class
-    CH = 3,
+    CH,
     /// Hesiod [Dyer 87]
-    HS = 4,
+    HS,
     /// Any class
-    Any = 255,
+    Any,
+    /// Any class not known to this library (RFC 3597)
+    Unknown(u16),
 }
 
 /// The OPCODE value according to RFC 1035
@@ -141,21 +225,29 @@ pub enum Opcode {
     Reserved(u16),
 }
 
-quick_error! {
-    /// The RCODE value according to RFC 1035
-    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-    #[allow(missing_docs)] // names are from spec
-    pub enum ResponseCode {
-        NoError
-        FormatError
-        ServerFailure
-        NameError
-        NotImplemented
-        Refused
-        Reserved(code: u8)
+/// The RCODE value according to RFC 1035
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[allow(missing_docs)] // names are from spec
+pub enum ResponseCode {
+    NoError,
+    FormatError,
+    ServerFailure,
+    NameError,
+    NotImplemented,
+    Refused,
+    Reserved(u8),
+}
+
+// Response codes are displayed by name, the same way as `Debug`
+impl fmt::Display for ResponseCode {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, fmt)
     }
 }
 
+#[cfg(feature = "std")]
+impl ::std::error::Error for ResponseCode {}
+
 impl From<u16> for Opcode {
     fn from(code: u16) -> Opcode {
         use self::Opcode::*;
@@ -211,89 +303,286 @@ impl Into<u8> for ResponseCode {
 
 impl QueryType {
     /// Parse a query type code
+    ///
+    /// Codes not known to this library are returned as `Unknown`, so this
+    /// never fails. The `Result` is kept for backwards compatibility.
     pub fn parse(code: u16) -> Result<QueryType, Error> {
+        Ok(code.into())
+    }
+}
+
+impl From<u16> for QueryType {
+    fn from(code: u16) -> QueryType {
         use self::QueryType::*;
         match code as isize {
-            a::Record::TYPE         => Ok(A),
-            ns::Record::TYPE        => Ok(NS),
-            mf::Record::TYPE        => Ok(MF),
-            cname::Record::TYPE     => Ok(CNAME),
-            soa::Record::TYPE       => Ok(SOA),
-            mb::Record::TYPE        => Ok(MB),
-            mg::Record::TYPE        => Ok(MG),
-            mr::Record::TYPE        => Ok(MR),
-            null::Record::TYPE      => Ok(NULL),
-            wks::Record::TYPE       => Ok(WKS),
-            ptr::Record::TYPE       => Ok(PTR),
-            hinfo::Record::TYPE     => Ok(HINFO),
-            minfo::Record::TYPE     => Ok(MINFO),
-            mx::Record::TYPE        => Ok(MX),
-            txt::Record::TYPE       => Ok(TXT),
-            aaaa::Record::TYPE      => Ok(AAAA),
-            srv::Record::TYPE       => Ok(SRV),
-            axfr::Record::TYPE      => Ok(AXFR),
-            mailb::Record::TYPE     => Ok(MAILB),
-            maila::Record::TYPE     => Ok(MAILA),
-            all::Record::TYPE       => Ok(All),
-            x               => Err(Error::InvalidQueryType(x as u16)),
+            a::Record::TYPE         => A,
+            ns::Record::TYPE        => NS,
+            mf::Record::TYPE        => MF,
+            cname::Record::TYPE     => CNAME,
+            soa::Record::TYPE       => SOA,
+            mb::Record::TYPE        => MB,
+            mg::Record::TYPE        => MG,
+            mr::Record::TYPE        => MR,
+            null::Record::TYPE      => NULL,
+            wks::Record::TYPE       => WKS,
+            ptr::Record::TYPE       => PTR,
+            hinfo::Record::TYPE     => HINFO,
+            minfo::Record::TYPE     => MINFO,
+            mx::Record::TYPE        => MX,
+            txt::Record::TYPE       => TXT,
+            aaaa::Record::TYPE      => AAAA,
+            srv::Record::TYPE       => SRV,
+            axfr::Record::TYPE      => AXFR,
+            mailb::Record::TYPE     => MAILB,
+            maila::Record::TYPE     => MAILA,
+            all::Record::TYPE       => All,
+            nsec::Record::TYPE      => NSEC,
+            ds::Record::TYPE        => DS,
+            rrsig::Record::TYPE     => RRSIG,
+            dnskey::Record::TYPE    => DNSKEY,
+            nsec3::Record::TYPE     => NSEC3,
+            nsec3param::Record::TYPE => NSEC3PARAM,
+            cds::Record::TYPE       => CDS,
+            cdnskey::Record::TYPE   => CDNSKEY,
+            sshfp::Record::TYPE     => SSHFP,
+            tlsa::Record::TYPE      => TLSA,
+            smimea::Record::TYPE    => SMIMEA,
+            openpgpkey::Record::TYPE => OPENPGPKEY,
+            caa::Record::TYPE       => CAA,
+            svcb::Record::TYPE      => SVCB,
+            https::Record::TYPE     => HTTPS,
+            loc::Record::TYPE       => LOC,
+            naptr::Record::TYPE     => NAPTR,
+            dname::Record::TYPE     => DNAME,
+            uri::Record::TYPE       => URI,
+            _                       => Unknown(code),
         }
     }
 }
 
+impl From<QueryType> for u16 {
+    fn from(qtype: QueryType) -> u16 {
+        use self::QueryType::*;
+        let code = match qtype {
+            A           => a::Record::TYPE,
+            NS          => ns::Record::TYPE,
+            MF          => mf::Record::TYPE,
+            CNAME       => cname::Record::TYPE,
+            SOA         => soa::Record::TYPE,
+            MB          => mb::Record::TYPE,
+            MG          => mg::Record::TYPE,
+            MR          => mr::Record::TYPE,
+            NULL        => null::Record::TYPE,
+            WKS         => wks::Record::TYPE,
+            PTR         => ptr::Record::TYPE,
+            HINFO       => hinfo::Record::TYPE,
+            MINFO       => minfo::Record::TYPE,
+            MX          => mx::Record::TYPE,
+            TXT         => txt::Record::TYPE,
+            AAAA        => aaaa::Record::TYPE,
+            SRV         => srv::Record::TYPE,
+            AXFR        => axfr::Record::TYPE,
+            MAILB       => mailb::Record::TYPE,
+            MAILA       => maila::Record::TYPE,
+            All         => all::Record::TYPE,
+            NSEC        => nsec::Record::TYPE,
+            DS          => ds::Record::TYPE,
+            RRSIG       => rrsig::Record::TYPE,
+            DNSKEY      => dnskey::Record::TYPE,
+            NSEC3       => nsec3::Record::TYPE,
+            NSEC3PARAM  => nsec3param::Record::TYPE,
+            CDS         => cds::Record::TYPE,
+            CDNSKEY     => cdnskey::Record::TYPE,
+            SSHFP       => sshfp::Record::TYPE,
+            TLSA        => tlsa::Record::TYPE,
+            SMIMEA      => smimea::Record::TYPE,
+            OPENPGPKEY  => openpgpkey::Record::TYPE,
+            CAA         => caa::Record::TYPE,
+            SVCB        => svcb::Record::TYPE,
+            HTTPS       => https::Record::TYPE,
+            LOC         => loc::Record::TYPE,
+            NAPTR       => naptr::Record::TYPE,
+            DNAME       => dname::Record::TYPE,
+            URI         => uri::Record::TYPE,
+            Unknown(x)  => return x,
+        };
+        code as u16
+    }
+}
+
 impl QueryClass {
     /// Parse a query class code
+    ///
+    /// Codes not known to this library are returned as `Unknown`, so this
+    /// never fails. The `Result` is kept for backwards compatibility.
     pub fn parse(code: u16) -> Result<QueryClass, Error> {
+        Ok(code.into())
+    }
+}
+
+impl From<u16> for QueryClass {
+    fn from(code: u16) -> QueryClass {
         use self::QueryClass::*;
         match code {
-            1   => Ok(IN),
-            2   => Ok(CS),
-            3   => Ok(CH),
-            4   => Ok(HS),
-            255 => Ok(Any),
-            x   => Err(Error::InvalidQueryClass(x)),
+            1   => IN,
+            2   => CS,
+            3   => CH,
+            4   => HS,
+            255 => Any,
+            x   => Unknown(x),
+        }
+    }
+}
+
+impl From<QueryClass> for u16 {
+    fn from(qclass: QueryClass) -> u16 {
+        use self::QueryClass::*;
+        match qclass {
+            IN          => 1,
+            CS          => 2,
+            CH          => 3,
+            HS          => 4,
+            Any         => 255,
+            Unknown(x)  => x,
         }
     }
 }
 
 impl Type {
     /// Parse a type code
+    ///
+    /// Codes not known to this library are returned as `Unknown`, so this
+    /// never fails. The `Result` is kept for backwards compatibility.
     pub fn parse(code: u16) -> Result<Type, Error> {
+        Ok(code.into())
+    }
+}
+
+impl From<u16> for Type {
+    fn from(code: u16) -> Type {
         use self::Type::*;
         match code as isize {
-            a::Record::TYPE         => Ok(A),
-            ns::Record::TYPE        => Ok(NS),
-            mf::Record::TYPE        => Ok(MF),
-            cname::Record::TYPE     => Ok(CNAME),
-            soa::Record::TYPE       => Ok(SOA),
-            mb::Record::TYPE        => Ok(MB),
-            mg::Record::TYPE        => Ok(MG),
-            mr::Record::TYPE        => Ok(MR),
-            null::Record::TYPE      => Ok(NULL),
-            wks::Record::TYPE       => Ok(WKS),
-            ptr::Record::TYPE       => Ok(PTR),
-            hinfo::Record::TYPE     => Ok(HINFO),
-            minfo::Record::TYPE     => Ok(MINFO),
-            mx::Record::TYPE        => Ok(MX),
-            txt::Record::TYPE       => Ok(TXT),
-            aaaa::Record::TYPE      => Ok(AAAA),
-            srv::Record::TYPE       => Ok(SRV),
-            opt::Record::TYPE       => Ok(OPT),
-            nsec::Record::TYPE      => Ok(NSEC),
-            x               => Err(Error::InvalidType(x as u16)),
+            a::Record::TYPE         => A,
+            ns::Record::TYPE        => NS,
+            mf::Record::TYPE        => MF,
+            cname::Record::TYPE     => CNAME,
+            soa::Record::TYPE       => SOA,
+            mb::Record::TYPE        => MB,
+            mg::Record::TYPE        => MG,
+            mr::Record::TYPE        => MR,
+            null::Record::TYPE      => NULL,
+            wks::Record::TYPE       => WKS,
+            ptr::Record::TYPE       => PTR,
+            hinfo::Record::TYPE     => HINFO,
+            minfo::Record::TYPE     => MINFO,
+            mx::Record::TYPE        => MX,
+            txt::Record::TYPE       => TXT,
+            aaaa::Record::TYPE      => AAAA,
+            srv::Record::TYPE       => SRV,
+            opt::Record::TYPE       => OPT,
+            nsec::Record::TYPE      => NSEC,
+            ds::Record::TYPE        => DS,
+            rrsig::Record::TYPE     => RRSIG,
+            dnskey::Record::TYPE    => DNSKEY,
+            nsec3::Record::TYPE     => NSEC3,
+            nsec3param::Record::TYPE => NSEC3PARAM,
+            cds::Record::TYPE       => CDS,
+            cdnskey::Record::TYPE   => CDNSKEY,
+            sshfp::Record::TYPE     => SSHFP,
+            tlsa::Record::TYPE      => TLSA,
+            smimea::Record::TYPE    => SMIMEA,
+            openpgpkey::Record::TYPE => OPENPGPKEY,
+            caa::Record::TYPE       => CAA,
+            svcb::Record::TYPE      => SVCB,
+            https::Record::TYPE     => HTTPS,
+            loc::Record::TYPE       => LOC,
+            naptr::Record::TYPE     => NAPTR,
+            dname::Record::TYPE     => DNAME,
+            uri::Record::TYPE       => URI,
+            _                       => Unknown(code),
         }
     }
 }
 
+impl From<Type> for u16 {
+    fn from(typ: Type) -> u16 {
+        use self::Type::*;
+        let code = match typ {
+            A           => a::Record::TYPE,
+            NS          => ns::Record::TYPE,
+            MF          => mf::Record::TYPE,
+            CNAME       => cname::Record::TYPE,
+            SOA         => soa::Record::TYPE,
+            MB          => mb::Record::TYPE,
+            MG          => mg::Record::TYPE,
+            MR          => mr::Record::TYPE,
+            NULL        => null::Record::TYPE,
+            WKS         => wks::Record::TYPE,
+            PTR         => ptr::Record::TYPE,
+            HINFO       => hinfo::Record::TYPE,
+            MINFO       => minfo::Record::TYPE,
+            MX          => mx::Record::TYPE,
+            TXT         => txt::Record::TYPE,
+            AAAA        => aaaa::Record::TYPE,
+            SRV         => srv::Record::TYPE,
+            OPT         => opt::Record::TYPE,
+            NSEC        => nsec::Record::TYPE,
+            DS          => ds::Record::TYPE,
+            RRSIG       => rrsig::Record::TYPE,
+            DNSKEY      => dnskey::Record::TYPE,
+            NSEC3       => nsec3::Record::TYPE,
+            NSEC3PARAM  => nsec3param::Record::TYPE,
+            CDS         => cds::Record::TYPE,
+            CDNSKEY     => cdnskey::Record::TYPE,
+            SSHFP       => sshfp::Record::TYPE,
+            TLSA        => tlsa::Record::TYPE,
+            SMIMEA      => smimea::Record::TYPE,
+            OPENPGPKEY  => openpgpkey::Record::TYPE,
+            CAA         => caa::Record::TYPE,
+            SVCB        => svcb::Record::TYPE,
+            HTTPS       => https::Record::TYPE,
+            LOC         => loc::Record::TYPE,
+            NAPTR       => naptr::Record::TYPE,
+            DNAME       => dname::Record::TYPE,
+            URI         => uri::Record::TYPE,
+            Unknown(x)  => return x,
+        };
+        code as u16
+    }
+}
+
 impl Class {
     /// Parse a class code
+    ///
+    /// Codes not known to this library are returned as `Unknown`, so this
+    /// never fails. The `Result` is kept for backwards compatibility.
     pub fn parse(code: u16) -> Result<Class, Error> {
+        Ok(code.into())
+    }
+}
+
+impl From<u16> for Class {
+    fn from(code: u16) -> Class {
         use self::Class::*;
         match code {
-            1   => Ok(IN),
-            2   => Ok(CS),
-            3   => Ok(CH),
-            4   => Ok(HS),
-            x   => Err(Error::InvalidClass(x)),
+            1   => IN,
+            2   => CS,
+            3   => CH,
+            4   => HS,
+            x   => Unknown(x),
+        }
+    }
+}
+
+impl From<Class> for u16 {
+    fn from(cls: Class) -> u16 {
+        use self::Class::*;
+        match cls {
+            IN          => 1,
+            CS          => 2,
+            CH          => 3,
+            HS          => 4,
+            Unknown(x)  => x,
         }
     }
 }
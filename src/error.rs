@@ -1,71 +1,154 @@
-use std::str::Utf8Error;
+use core::fmt;
+use core::str::Utf8Error;
 
-quick_error! {
-    /// Error parsing DNS packet
-    #[derive(Debug)]
-    pub enum Error {
-        /// Invalid compression pointer not pointing backwards
-        /// when parsing label
-        BadPointer {
-            description("invalid compression pointer not pointing backwards \
-                         when parsing label")
-        }
-        /// Packet is smaller than header size
-        HeaderTooShort {
-            description("packet is smaller than header size")
-        }
-        /// Packet ihas incomplete data
-        UnexpectedEOF {
-            description("packet is has incomplete data")
-        }
-        /// Wrong (too short or too long) size of RDATA
-        WrongRdataLength {
-            description("wrong (too short or too long) size of RDATA")
-        }
-        /// Packet has non-zero reserved bits
-        ReservedBitsAreNonZero {
-            description("packet has non-zero reserved bits")
-        }
-        /// Label in domain name has unknown label format
-        UnknownLabelFormat {
-            description("label in domain name has unknown label format")
-        }
-        /// Query type code is invalid
-        InvalidQueryType(code: u16) {
-            description("query type code is invalid")
-            display("query type {} is invalid", code)
-        }
-        /// Query class code is invalid
-        InvalidQueryClass(code: u16) {
-            description("query class code is invalid")
-            display("query class {} is invalid", code)
-        }
-        /// Type code is invalid
-        InvalidType(code: u16) {
-            description("type code is invalid")
-            display("type {} is invalid", code)
-        }
-        /// Class code is invalid
-        InvalidClass(code: u16) {
-            description("class code is invalid")
-            display("class {} is invalid", code)
-        }
-        /// Invalid characters encountered while reading label
-        LabelIsNotAscii {
-            description("invalid characters encountered while reading label")
-        }
-        /// Invalid characters encountered while reading TXT
-        TxtDataIsNotUTF8(error: Utf8Error) {
-            description("invalid characters encountered while reading TXT")
-            display("{:?}", error)
-        }
-        /// Parser is in the wrong state
-        WrongState {
-            description("parser is in the wrong state")
-        }
-        /// Additional OPT record found
-        AdditionalOPT {
-            description("additional OPT record found")
+/// Error parsing DNS packet
+#[derive(Debug)]
+pub enum Error {
+    /// Invalid compression pointer not pointing backwards
+    /// when parsing label
+    BadPointer,
+    /// Packet is smaller than header size
+    HeaderTooShort,
+    /// Packet ihas incomplete data
+    UnexpectedEOF,
+    /// Wrong (too short or too long) size of RDATA
+    WrongRdataLength,
+    /// Packet has non-zero reserved bits
+    ReservedBitsAreNonZero,
+    /// Label in domain name has unknown label format
+    UnknownLabelFormat,
+    /// Query type code is invalid
+    InvalidQueryType(u16),
+    /// Query class code is invalid
+    InvalidQueryClass(u16),
+    /// Type code is invalid
+    InvalidType(u16),
+    /// Class code is invalid
+    InvalidClass(u16),
+    /// Invalid characters encountered while reading label
+    ///
+    /// Not returned any more, labels may contain arbitrary bytes
+    LabelIsNotAscii,
+    /// Invalid characters encountered while reading TXT
+    TxtDataIsNotUTF8(Utf8Error),
+    /// Parser is in the wrong state
+    WrongState,
+    /// Additional OPT record found
+    AdditionalOPT,
+    /// EDNS option has invalid length or content
+    BadEdnsOption(u16),
+    /// SVCB parameter has invalid value or is out of order
+    BadSvcParam(u16),
+    /// Label of a domain name is longer than 63 bytes
+    LabelTooLong,
+    /// Domain name is longer than 255 bytes in wire format
+    NameTooLong,
+    /// Domain name contains an empty label, e.g. two dots in a row
+    EmptyLabel,
+    /// Invalid backslash escape in a domain name
+    BadEscape,
+    /// Domain name can't be converted to or from IDNA form (UTS #46)
+    InvalidIdn,
+    /// CAA record tag is not alphanumeric
+    InvalidCaaTag,
+}
+
+/// Error building DNS packet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    /// Data being written doesn't fit into the buffer
+    BufferFull,
+    /// Domain name is longer than 255 bytes in wire format
+    NameTooLong,
+    /// Label of a domain name is longer than 63 bytes
+    LabelTooLong,
+    /// Domain name has an empty label or invalid escape
+    InvalidName,
+    /// Record is added to a section preceding one already written
+    SectionOrder,
+    /// Section already contains 65535 records
+    CountOverflow,
+    /// Record data or EDNS options are longer than 65535 bytes
+    RecordDataTooLong,
+    /// Character string in record data is longer than 255 bytes
+    StringTooLong,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use self::Error::*;
+        match *self {
+            BadPointer => fmt.write_str("invalid compression pointer not \
+                pointing backwards when parsing label"),
+            HeaderTooShort => fmt.write_str("packet is smaller than header size"),
+            UnexpectedEOF => fmt.write_str("packet is has incomplete data"),
+            WrongRdataLength => {
+                fmt.write_str("wrong (too short or too long) size of RDATA")
+            }
+            ReservedBitsAreNonZero => {
+                fmt.write_str("packet has non-zero reserved bits")
+            }
+            UnknownLabelFormat => {
+                fmt.write_str("label in domain name has unknown label format")
+            }
+            InvalidQueryType(code) => {
+                write!(fmt, "query type {} is invalid", code)
+            }
+            InvalidQueryClass(code) => {
+                write!(fmt, "query class {} is invalid", code)
+            }
+            InvalidType(code) => write!(fmt, "type {} is invalid", code),
+            InvalidClass(code) => write!(fmt, "class {} is invalid", code),
+            LabelIsNotAscii => fmt.write_str("invalid characters encountered \
+                while reading label"),
+            TxtDataIsNotUTF8(ref error) => write!(fmt, "{:?}", error),
+            WrongState => fmt.write_str("parser is in the wrong state"),
+            AdditionalOPT => fmt.write_str("additional OPT record found"),
+            BadEdnsOption(code) => {
+                write!(fmt, "EDNS option {} is malformed", code)
+            }
+            BadSvcParam(key) => {
+                write!(fmt, "SVCB parameter {} is malformed", key)
+            }
+            LabelTooLong => {
+                fmt.write_str("label of a domain name is longer than 63 bytes")
+            }
+            NameTooLong => {
+                fmt.write_str("domain name is longer than 255 bytes")
+            }
+            EmptyLabel => fmt.write_str("domain name contains an empty label"),
+            BadEscape => {
+                fmt.write_str("invalid backslash escape in a domain name")
+            }
+            InvalidIdn => {
+                fmt.write_str("invalid internationalized domain name")
+            }
+            InvalidCaaTag => {
+                fmt.write_str("CAA record tag is not alphanumeric")
+            }
         }
     }
 }
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for Error {}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use self::BuildError::*;
+        let text = match *self {
+            BufferFull => "data doesn't fit into the buffer",
+            NameTooLong => "domain name is longer than 255 bytes",
+            LabelTooLong => "label of a domain name is longer than 63 bytes",
+            InvalidName => "domain name has an empty label or invalid escape",
+            SectionOrder => "sections must be filled in order",
+            CountOverflow => "too many records in a section",
+            RecordDataTooLong => "record data is longer than 65535 bytes",
+            StringTooLong => "character string is longer than 255 bytes",
+        };
+        fmt.write_str(text)
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for BuildError {}
@@ -1,6 +1,6 @@
 use byteorder::{BigEndian, ByteOrder};
 
-use {Error, ResponseCode, Opcode};
+use {Error, BuildError, ResponseCode, Opcode};
 
 mod flag {
     pub const QUERY:               u16 = 0b1000_0000_0000_0000;
@@ -73,6 +73,15 @@ impl Header {
         if data.len() != 12 {
             panic!("Header size is exactly 12 bytes");
         }
+        self.try_write(data).expect("slice length is checked");
+    }
+    /// Write a header to the first 12 bytes of a buffer slice
+    ///
+    /// Returns an error if the buffer is shorter than 12 bytes.
+    pub fn try_write(&self, data: &mut [u8]) -> Result<(), BuildError> {
+        if data.len() < 12 {
+            return Err(BuildError::BufferFull);
+        }
         let mut flags = 0u16;
         flags |= Into::<u16>::into(self.opcode)
             << flag::OPCODE_MASK.trailing_zeros();
@@ -82,18 +91,21 @@ impl Header {
         if self.recursion_desired { flags |= flag::RECURSION_DESIRED; }
         if self.recursion_available { flags |= flag::RECURSION_AVAILABLE; }
         if self.truncated { flags |= flag::TRUNCATED; }
+        if self.authenticated_data { flags |= flag::AUTHENTICATED_DATA; }
+        if self.checking_disabled { flags |= flag::CHECKING_DISABLED; }
         BigEndian::write_u16(&mut data[..2], self.id);
         BigEndian::write_u16(&mut data[2..4], flags);
         BigEndian::write_u16(&mut data[4..6], self.questions);
         BigEndian::write_u16(&mut data[6..8], self.answers);
         BigEndian::write_u16(&mut data[8..10], self.nameservers);
         BigEndian::write_u16(&mut data[10..12], self.additional);
+        Ok(())
     }
     /// Set "truncated flag" in the raw data
     // shouldn't this method be non-public?
     pub fn set_truncated(data: &mut [u8]) {
         let oldflags = BigEndian::read_u16(&data[2..4]);
-        BigEndian::write_u16(&mut data[2..4], oldflags & flag::TRUNCATED);
+        BigEndian::write_u16(&mut data[2..4], oldflags | flag::TRUNCATED);
     }
     /// Returns a size of the header (always 12 bytes)
     pub fn size() -> usize { 12 }
@@ -103,7 +115,7 @@ impl Header {
 #[cfg(test)]
 mod test {
 
-    use {Header};
+    use {Header, BuildError};
     use Opcode::*;
     use ResponseCode::NoError;
 
@@ -130,6 +142,29 @@ mod test {
         });
     }
 
+    #[test]
+    fn set_truncated() {
+        let mut data = *b"\x06%\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00";
+        Header::set_truncated(&mut data);
+        let header = Header::parse(&data).unwrap();
+        assert!(header.truncated);
+        assert!(header.recursion_desired);
+        assert!(header.recursion_available);
+        assert!(!header.query);
+        assert_eq!(header.answers, 1);
+    }
+
+    #[test]
+    fn try_write() {
+        let header = Header::parse(b"\x06%\x01\x00\x00\x01\x00\x00\x00\x00\x00\x00")
+            .unwrap();
+        let mut data = [0xFFu8; 14];
+        header.try_write(&mut data).unwrap();
+        assert_eq!(&data, b"\x06%\x01\x00\x00\x01\x00\x00\x00\x00\x00\x00\xFF\xFF");
+        assert_matches!(header.try_write(&mut data[..11]),
+                        Err(BuildError::BufferFull));
+    }
+
     #[test]
     fn parse_example_response() {
         let response = b"\x06%\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
@@ -200,4 +235,27 @@ mod test {
             additional: 0,
         });
     }
+
+    #[test]
+    fn write_parse_roundtrip() {
+        let header = Header {
+            id: 1573,
+            query: false,
+            opcode: StandardQuery,
+            authoritative: true,
+            truncated: false,
+            recursion_desired: true,
+            recursion_available: true,
+            authenticated_data: true,
+            checking_disabled: true,
+            response_code: NoError,
+            questions: 1,
+            answers: 2,
+            nameservers: 3,
+            additional: 4,
+        };
+        let mut buf = [0u8; 12];
+        header.write(&mut buf);
+        assert_eq!(Header::parse(&buf).unwrap(), header);
+    }
 }
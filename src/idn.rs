@@ -0,0 +1,119 @@
+//! Internationalized domain names (UTS #46), enabled by `idna` feature
+use idna::Config;
+
+use {Error, Name, OwnedName};
+use name::write_label;
+
+/// Nontransitional processing as recommended by UTS #46, but without STD3
+/// rules, so names like `_sip._udp.example` are still accepted
+fn config() -> Config {
+    Config::default()
+        .check_hyphens(true)
+        .use_std3_ascii_rules(false)
+}
+
+impl OwnedName {
+    /// Converts a Unicode domain name to A-labels (`xn--...`)
+    ///
+    /// Labels are mapped and normalized according to UTS #46 first, so
+    /// the result is always lowercase. Names which are ASCII already are
+    /// parsed as is, including backslash escapes.
+    ///
+    /// Textual form of the result can be passed to
+    /// `Builder::add_question`:
+    ///
+    /// ```
+    /// # use dns_parser::{Builder, OwnedName, QueryType, QueryClass};
+    /// let name = OwnedName::from_unicode("bücher.example").unwrap();
+    /// let mut builder = Builder::new_query(1, true);
+    /// builder.add_question(&name.to_string(), false,
+    ///                      QueryType::A, QueryClass::IN);
+    /// ```
+    pub fn from_unicode(name: &str) -> Result<OwnedName, Error> {
+        if name.is_ascii() && !name.contains("xn--") {
+            return name.parse();
+        }
+        let ascii = config().to_ascii(name)
+            .map_err(|_| Error::InvalidIdn)?;
+        ascii.parse()
+    }
+}
+
+impl<'a> Name<'a> {
+    /// Returns the name with A-labels (`xn--...`) decoded to Unicode
+    ///
+    /// This is for displaying names to users. Labels which are not
+    /// valid A-labels are written in the same escaped form as by
+    /// `Display`.
+    pub fn to_unicode(&self) -> String {
+        let mut result = String::new();
+        for (idx, label) in self.labels().enumerate() {
+            if idx != 0 {
+                result.push('.');
+            }
+            if let Some(unicode) = decode_label(label) {
+                result.push_str(&unicode);
+            } else {
+                write_label(&mut result, label).unwrap();
+            }
+        }
+        result
+    }
+}
+
+fn decode_label(label: &[u8]) -> Option<String> {
+    if label.len() < 4 || !label[..4].eq_ignore_ascii_case(b"xn--") {
+        return None;
+    }
+    let label = match ::core::str::from_utf8(label) {
+        Ok(label) if label.bytes().all(|b| b.is_ascii_graphic() && b != b'.')
+            => label,
+        _ => return None,
+    };
+    match config().to_unicode(label) {
+        (unicode, Ok(())) => Some(unicode),
+        (_, Err(_)) => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {Builder, Error, Name, OwnedName, Packet, QueryType, QueryClass};
+
+    #[test]
+    fn from_unicode() {
+        let name = OwnedName::from_unicode("Bücher.example.").unwrap();
+        assert_eq!(name.to_string(), "xn--bcher-kva.example");
+        let name = OwnedName::from_unicode("_sip._udp.ПРИМЕР.рф").unwrap();
+        assert_eq!(name.to_string(), "_sip._udp.xn--e1afmkfd.xn--p1ai");
+        let name = OwnedName::from_unicode("a\\.b.example").unwrap();
+        assert_eq!(name.as_bytes(), b"\x03a.b\x07example\x00");
+        assert_matches!(OwnedName::from_unicode("xn--a.example"),
+                        Err(Error::InvalidIdn));
+        assert_matches!(OwnedName::from_unicode("-bücher.example"),
+                        Err(Error::InvalidIdn));
+    }
+
+    #[test]
+    fn to_unicode() {
+        let name = "www.XN--bcher-kva.example".parse::<OwnedName>().unwrap();
+        assert_eq!(name.as_name().to_unicode(), "www.bücher.example");
+        let name = "xn--zz.a\\032b".parse::<OwnedName>().unwrap();
+        assert_eq!(name.as_name().to_unicode(), "xn--zz.a\\032b");
+        assert_eq!(Name::from_uncompressed(b"\x00").to_unicode(), "");
+    }
+
+    #[test]
+    fn question_roundtrip() {
+        let name = OwnedName::from_unicode("пример.испытание").unwrap();
+        let mut builder = Builder::new_query(1, true);
+        builder.add_question(&name.to_string(), false,
+                             QueryType::A, QueryClass::IN);
+        let data = builder.build().unwrap();
+        let packet = Packet::parse(&data).unwrap();
+        assert_eq!(packet.questions[0].qname.to_string(),
+                   "xn--e1afmkfd.xn--80akhbyknj4f");
+        assert_eq!(packet.questions[0].qname.to_unicode(),
+                   "пример.испытание");
+    }
+}
@@ -1,4 +1,3 @@
-#![recursion_limit="100"]
 //! The network-agnostic DNS parser library
 //!
 //! [Documentation](https://docs.rs/dns-parser) |
@@ -12,13 +11,27 @@
 //! [`Builder`]: struct.Builder.html
 //! [`Packet::parse`]: struct.Packet.html#method.parse
 //!
+//! The crate is `no_std` (requiring only `alloc`) when the default `std`
+//! feature is disabled.
+//!
 #![warn(missing_docs)]
 #![warn(missing_debug_implementations)]
+// Suggested replacements need a much newer compiler than we support
+#![allow(unknown_lints, clippy::manual_is_multiple_of, clippy::manual_div_ceil)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+// Injected automatically in `no_std` mode, so `core::` paths work in both
+#[cfg(any(feature = "std", test))] extern crate core;
+#[cfg_attr(not(any(feature = "std", test)), macro_use)] extern crate alloc;
 extern crate byteorder;
 #[cfg(test)] #[macro_use] extern crate matches;
-#[macro_use(quick_error)] extern crate quick_error;
+#[cfg(test)] extern crate quickcheck;
 #[cfg(feature = "with-serde")] #[macro_use] extern crate serde_derive;
+#[cfg(feature = "idna")] extern crate idna;
+
+// `core::net` is only needed for `no_std` and requires a newer compiler
+#[cfg(any(feature = "std", test))] use std::net;
+#[cfg(not(any(feature = "std", test)))] use core::net;
 
 mod enums;
 mod structs;
@@ -27,13 +40,20 @@ mod parser;
 mod error;
 mod header;
 mod builder;
+mod owned;
+#[cfg(feature = "idna")] mod idn;
+mod reverse;
+mod view;
 
 pub mod rdata;
 
 pub use enums::{Type, QueryType, Class, QueryClass, ResponseCode, Opcode};
 pub use structs::{Question, ResourceRecord, Packet};
-pub use name::{Name};
-pub use error::{Error};
+pub use view::{PacketView, QuestionIter, RecordIter};
+pub use name::{Name, Labels};
+pub use error::{Error, BuildError};
 pub use header::{Header};
 pub use rdata::{RData};
-pub use builder::{Builder};
+pub use builder::{Builder, RecordData, Buffer, SliceBuffer, Truncated};
+pub use owned::{OwnedName, OwnedRData, OwnedQuestion, OwnedRecord, OwnedOpt};
+pub use owned::{OwnedPacket};
@@ -1,10 +1,8 @@
-use std::fmt;
-use std::fmt::Write;
-use std::str::from_utf8;
-
-// Deprecated since rustc 1.23
-#[allow(unused_imports, deprecated)]
-use std::ascii::AsciiExt;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use alloc::string::String;
+use alloc::vec::Vec;
 
 use byteorder::{BigEndian, ByteOrder};
 
@@ -28,6 +26,7 @@ impl<'a> Name<'a> {
     /// The `data` should be a part of `original` where name should start.
     /// The `original` is the data starting a the start of a packet, so
     /// that offsets in compressed name starts from the `original`.
+    /// Names longer than 255 bytes after decompression are rejected.
     pub fn scan(data: &'a[u8], original: &'a[u8]) -> Result<Name<'a>, Error> {
         let mut parse_data = data;
         let mut return_pos = None;
@@ -38,6 +37,8 @@ impl<'a> Name<'a> {
         // By setting the largest_pos to be the original len, a side effect
         // is that the pos variable can move forwards in the buffer once.
         let mut largest_pos = original.len();
+        // Length of the name without compression, including the root label
+        let mut name_len = 1;
         let mut byte = parse_data[pos];
         while byte != 0 {
             if parse_data.len() <= pos {
@@ -69,12 +70,13 @@ impl<'a> Name<'a> {
                 parse_data = &original[off..];
             } else if byte & 0b1100_0000 == 0 {
                 let end = pos + byte as usize + 1;
+                name_len += byte as usize + 1;
+                if name_len > 255 {
+                    return Err(Error::NameTooLong);
+                }
                 if parse_data.len() < end {
                     return Err(Error::UnexpectedEOF);
                 }
-                if !parse_data[pos+1..end].is_ascii() {
-                    return Err(Error::LabelIsNotAscii);
-                }
                 pos = end;
                 if parse_data.len() <= pos {
                     return Err(Error::UnexpectedEOF);
@@ -94,39 +96,244 @@ impl<'a> Name<'a> {
     pub fn byte_len(&self) -> usize {
         self.labels.len()
     }
+    /// Creates a name from wire bytes which contain no compression
+    /// pointers and were already validated
+    pub(crate) fn from_uncompressed(data: &'a [u8]) -> Name<'a> {
+        Name { labels: data, original: data }
+    }
+    /// Returns iterator over labels, starting from the leftmost one
+    pub fn labels(&self) -> Labels<'a> {
+        Labels {
+            data: self.labels,
+            original: self.original,
+        }
+    }
+    /// Number of labels, not counting the root label
+    pub fn label_count(&self) -> usize {
+        self.labels().count()
+    }
+    /// Returns true if this is the root name
+    pub fn is_root(&self) -> bool {
+        self.labels().next().is_none()
+    }
+    /// Returns true if the leftmost label is `*` (RFC 4592)
+    pub fn is_wildcard(&self) -> bool {
+        self.labels().next() == Some(b"*")
+    }
+    /// Returns the name with the leftmost label removed
+    ///
+    /// Returns `None` for the root name.
+    pub fn parent(&self) -> Option<Name<'a>> {
+        let mut data = self.labels;
+        loop {
+            let byte = data[0];
+            if byte & 0b1100_0000 == 0b1100_0000 {
+                let off = (BigEndian::read_u16(&data[..2])
+                           & !0b1100_0000_0000_0000) as usize;
+                data = &self.original[off..];
+                continue;
+            }
+            if byte == 0 {
+                return None;
+            }
+            let rest = &data[byte as usize + 1..];
+            return Some(Name {
+                labels: &rest[..wire_len(rest)],
+                original: self.original,
+            });
+        }
+    }
+    /// Returns true if rightmost labels of this name are equal to `suffix`
+    ///
+    /// Labels are compared case-insensitively. Every name ends with itself
+    /// and with the root name.
+    pub fn ends_with(&self, suffix: &Name) -> bool {
+        let count = self.label_count();
+        let suffix_count = suffix.label_count();
+        if suffix_count > count {
+            return false;
+        }
+        self.labels().skip(count - suffix_count).zip(suffix.labels())
+            .all(|(a, b)| a.eq_ignore_ascii_case(b))
+    }
+    /// Returns true if this name is strictly below `parent` in the tree
+    ///
+    /// Unlike `ends_with` it's false if names are equal.
+    pub fn is_subdomain_of(&self, parent: &Name) -> bool {
+        self.label_count() > parent.label_count() && self.ends_with(parent)
+    }
+    /// Appends the name in presentation format to `buf`
+    ///
+    /// Same as `Display`, but the buffer can be reused between calls to
+    /// avoid allocating a new string for every name.
+    pub fn write_to_string(&self, buf: &mut String) {
+        self.write_labels(buf).expect("writing to a string never fails")
+    }
+    fn write_labels<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        for (idx, label) in self.labels().enumerate() {
+            if idx != 0 {
+                out.write_char('.')?;
+            }
+            write_label(out, label)?;
+        }
+        Ok(())
+    }
+    /// Appends the name to the buffer, replacing compression pointers
+    /// by the labels they point to
+    pub(crate) fn write_uncompressed(&self, buf: &mut Vec<u8>) {
+        for label in self.labels() {
+            buf.push(label.len() as u8);
+            buf.extend_from_slice(label);
+        }
+        buf.push(0);
+    }
 }
 
-impl<'a> fmt::Display for Name<'a> {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let data = self.labels;
-        let original = self.original;
-        let mut pos = 0;
+/// Iterator over labels of the name, not including the root label
+///
+/// Labels are returned as raw bytes, without the length prefix.
+/// Compression pointers are followed transparently.
+#[derive(Debug, Clone)]
+pub struct Labels<'a> {
+    data: &'a [u8],
+    original: &'a [u8],
+}
+
+/// Number of bytes name occupies in place, up to the root label
+/// or the first compression pointer
+fn wire_len(data: &[u8]) -> usize {
+    let mut pos = 0;
+    loop {
+        let byte = data[pos];
+        if byte & 0b1100_0000 == 0b1100_0000 {
+            return pos + 2;
+        }
+        if byte == 0 {
+            return pos + 1;
+        }
+        pos += byte as usize + 1;
+    }
+}
+
+impl<'a> Iterator for Labels<'a> {
+    type Item = &'a [u8];
+    fn next(&mut self) -> Option<&'a [u8]> {
         loop {
-            let byte = data[pos];
-            if byte == 0 {
-                return Ok(());
-            } else if byte & 0b1100_0000 == 0b1100_0000 {
-                let off = (BigEndian::read_u16(&data[pos..pos+2])
+            let byte = self.data[0];
+            if byte & 0b1100_0000 == 0b1100_0000 {
+                let off = (BigEndian::read_u16(&self.data[..2])
                            & !0b1100_0000_0000_0000) as usize;
-                if pos != 0 {
-                    try!(fmt.write_char('.'));
-                }
-                return fmt::Display::fmt(
-                    &Name::scan(&original[off..], original).unwrap(), fmt)
-            } else if byte & 0b1100_0000 == 0 {
-                if pos != 0 {
-                    try!(fmt.write_char('.'));
-                }
-                let end = pos + byte as usize + 1;
-                try!(fmt.write_str(from_utf8(&data[pos+1..end]).unwrap()));
-                pos = end;
+                self.data = &self.original[off..];
                 continue;
-            } else {
-                unreachable!();
             }
+            if byte == 0 {
+                return None;
+            }
+            let end = byte as usize + 1;
+            let label = &self.data[1..end];
+            self.data = &self.data[end..];
+            return Some(label);
         }
     }
 }
+
+/// Writes label in presentation format (RFC 1035 Section 5.1)
+///
+/// Dots, backslashes and characters special in master files (`"`, `(`,
+/// `)`, `;`, `@` and `$`) are escaped with a backslash, other bytes which
+/// are not printable ASCII characters, including space, as `\DDD`.
+pub(crate) fn write_label<W: fmt::Write>(fmt: &mut W, label: &[u8]) -> fmt::Result {
+    for &byte in label {
+        match byte {
+            b'.' | b'\\' | b'"' | b'(' | b')' | b';' | b'@' | b'$' => {
+                fmt.write_char('\\')?;
+                fmt.write_char(byte as char)?;
+            }
+            0x21..=0x7E => fmt.write_char(byte as char)?,
+            _ => write!(fmt, "\\{:03}", byte)?,
+        }
+    }
+    Ok(())
+}
+
+impl<'a> fmt::Display for Name<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        self.write_labels(fmt)
+    }
+}
+/// Names are compared case-insensitively (for ASCII letters), no matter
+/// whether and how they are compressed
+impl<'a, 'b> PartialEq<Name<'b>> for Name<'a> {
+    fn eq(&self, other: &Name<'b>) -> bool {
+        let mut left = self.labels();
+        let mut right = other.labels();
+        loop {
+            match (left.next(), right.next()) {
+                (Some(a), Some(b)) if a.eq_ignore_ascii_case(b) => {}
+                (None, None) => return true,
+                _ => return false,
+            }
+        }
+    }
+}
+
+impl<'a> Eq for Name<'a> {}
+
+impl<'a> Hash for Name<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for label in self.labels() {
+            state.write_u8(label.len() as u8);
+            for byte in label {
+                state.write_u8(byte.to_ascii_lowercase());
+            }
+        }
+        state.write_u8(0);
+    }
+}
+
+/// Canonical DNS name order (RFC 4034 Section 6.1)
+///
+/// Names are sorted by their rightmost label first, labels are compared
+/// as lowercase byte strings, so `example` < `a.example` < `B.example`.
+impl<'a> Ord for Name<'a> {
+    fn cmp(&self, other: &Name<'a>) -> Ordering {
+        // Labels are collected on the stack to compare them right-to-left
+        let mut left = [&[][..]; MAX_LABELS];
+        let mut right = [&[][..]; MAX_LABELS];
+        let left = stack_labels(self, &mut left);
+        let right = stack_labels(other, &mut right);
+        for (a, b) in left.iter().rev().zip(right.iter().rev()) {
+            let ord = a.iter().map(u8::to_ascii_lowercase)
+                .cmp(b.iter().map(u8::to_ascii_lowercase));
+            if ord != Ordering::Equal {
+                return ord;
+            }
+        }
+        left.len().cmp(&right.len())
+    }
+}
+
+/// Maximum number of labels in a name of 255 bytes, not including root
+const MAX_LABELS: usize = 127;
+
+/// Stores labels of the name into `buf`, returns the filled part
+fn stack_labels<'x, 'b>(name: &Name<'x>, buf: &'b mut [&'x [u8]; MAX_LABELS])
+    -> &'b [&'x [u8]]
+{
+    let mut count = 0;
+    for label in name.labels() {
+        buf[count] = label;
+        count += 1;
+    }
+    &buf[..count]
+}
+
+impl<'a> PartialOrd for Name<'a> {
+    fn partial_cmp(&self, other: &Name<'a>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl<'a> fmt::Debug for Name<'a> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         fmt.debug_tuple("Name")
@@ -137,7 +344,10 @@ impl<'a> fmt::Debug for Name<'a> {
 
 #[cfg(test)]
 mod test {
-    use Error;
+    use std::cmp::Ordering;
+    use std::collections::HashSet;
+
+    use {Error, OwnedName};
     use Name;
 
     #[test]
@@ -180,4 +390,175 @@ mod test {
         assert_eq!(Name::scan(&buf[9..], buf).unwrap().labels,
             b"\x02zz\xc0\x04");
     }
+
+    #[test]
+    fn labels() {
+        let buf = b"\x03www\x07example\x03com\x00\x04mail\xc0\x04";
+        let name = Name::scan(&buf[17..], buf).unwrap();
+        assert_eq!(name.labels().collect::<Vec<_>>(),
+            vec![&b"mail"[..], &b"example"[..], &b"com"[..]]);
+        assert_eq!(name.label_count(), 3);
+        assert!(!name.is_root());
+        assert!(!name.is_wildcard());
+        let root = Name::scan(&buf[16..], buf).unwrap();
+        assert!(root.is_root());
+        assert_eq!(root.label_count(), 0);
+        assert!(root.parent().is_none());
+        let buf = b"\x01*\x07example\x00";
+        assert!(Name::scan(buf, buf).unwrap().is_wildcard());
+    }
+
+    #[test]
+    fn parent() {
+        let buf = b"\x03www\x07example\x03com\x00\x04mail\xc0\x04";
+        let name = Name::scan(&buf[17..], buf).unwrap();
+        let parent = name.parent().unwrap();
+        assert_eq!(parent.to_string(), "example.com");
+        // only the compression pointer is left in place
+        assert_eq!(parent.byte_len(), 2);
+        let parent = parent.parent().unwrap();
+        assert_eq!(parent.to_string(), "com");
+        let parent = parent.parent().unwrap();
+        assert!(parent.is_root());
+        assert!(parent.parent().is_none());
+        let www = Name::scan(buf, buf).unwrap();
+        assert_eq!(www.parent().unwrap().to_string(), "example.com");
+    }
+
+    #[test]
+    fn suffixes() {
+        let buf = b"\x03www\x07Example\x03COM\x00\x07example\x03com\x00\
+                    \x03net\x00\x02le\x03com\x00";
+        let www = Name::scan(buf, buf).unwrap();
+        let example = Name::scan(&buf[17..], buf).unwrap();
+        let net = Name::scan(&buf[30..], buf).unwrap();
+        let le = Name::scan(&buf[35..], buf).unwrap();
+        let root = Name::scan(&buf[16..], buf).unwrap();
+        assert!(www.ends_with(&example));
+        assert!(www.is_subdomain_of(&example));
+        assert!(example.ends_with(&example));
+        assert!(!example.is_subdomain_of(&example));
+        assert!(!example.ends_with(&www));
+        assert!(!www.ends_with(&net));
+        // whole labels are compared, not bytes
+        assert!(!example.ends_with(&le));
+        assert!(www.ends_with(&root));
+        assert!(root.ends_with(&root));
+        assert!(!root.is_subdomain_of(&root));
+    }
+
+    #[test]
+    fn equality() {
+        let buf = b"\x03www\x07example\x03com\x00\x03WWW\xc0\x04\
+                    \x03www\x07example\x03org\x00";
+        let plain = Name::scan(buf, buf).unwrap();
+        let compressed = Name::scan(&buf[17..], buf).unwrap();
+        let other = Name::scan(&buf[23..], buf).unwrap();
+        assert_eq!(plain, compressed);
+        assert_ne!(plain, other);
+        assert_ne!(plain, plain.parent().unwrap());
+
+        let mut set = HashSet::new();
+        set.insert(plain);
+        assert!(set.contains(&compressed));
+        assert!(!set.contains(&other));
+    }
+
+    #[test]
+    fn canonical_order() {
+        // Example from RFC 4034 Section 6.1
+        let sorted = [
+            "example",
+            "a.example",
+            "yljkjljk.a.example",
+            "Z.a.example",
+            "zABC.a.EXAMPLE",
+            "z.example",
+            "\\001.z.example",
+            "*.z.example",
+            "\\200.z.example",
+        ];
+        let names = sorted.iter().rev()
+            .map(|x| x.parse::<OwnedName>().unwrap())
+            .collect::<Vec<_>>();
+        let mut names = names.iter().map(|x| x.as_name()).collect::<Vec<_>>();
+        names.sort();
+        let names = names.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        assert_eq!(names, sorted);
+
+        // longest possible names, differing only in the leftmost label
+        let (buf, last) = pointer_chain(126);
+        let long = Name::scan(&buf[last..], &buf).unwrap();
+        let longer = format!("c.{}", long).parse::<OwnedName>();
+        assert_matches!(longer, Err(Error::NameTooLong));
+        let other = format!("c{}", &long.to_string()[1..])
+            .parse::<OwnedName>().unwrap();
+        assert_eq!(other.as_name().label_count(), 127);
+        assert!(long < other.as_name());
+        assert!(other.as_name() > long);
+        assert_eq!(long.cmp(&long), Ordering::Equal);
+    }
+
+    // Builds a name of `n` labels "b" followed by "a", where every
+    // label points to the previous one, all the way to the start
+    fn pointer_chain(n: usize) -> (Vec<u8>, usize) {
+        let mut buf = b"\x01a\x00".to_vec();
+        let mut last = 0;
+        for _ in 0..n {
+            let pos = buf.len();
+            buf.extend_from_slice(&[1, b'b', 0xC0 | (last >> 8) as u8,
+                                    last as u8]);
+            last = pos;
+        }
+        (buf, last)
+    }
+
+    #[test]
+    fn long_pointer_chain() {
+        // 126 labels of 2 bytes, "a" and root is exactly 255 bytes
+        let (buf, last) = pointer_chain(126);
+        let name = Name::scan(&buf[last..], &buf).unwrap();
+        assert_eq!(name.byte_len(), 4);
+        let text = name.to_string();
+        assert_eq!(text.len(), 253);
+        assert!(text.starts_with("b.b.b."));
+        assert!(text.ends_with(".b.a"));
+
+        let (buf, last) = pointer_chain(127);
+        assert_matches!(Name::scan(&buf[last..], &buf),
+                        Err(Error::NameTooLong));
+        let (buf, last) = pointer_chain(1000);
+        assert_matches!(Name::scan(&buf[last..], &buf),
+                        Err(Error::NameTooLong));
+    }
+
+    #[test]
+    fn write_to_string() {
+        let (chain, last) = pointer_chain(3);
+        let name = Name::scan(&chain[last..], &chain).unwrap();
+        let mut buf = String::new();
+        Name::from_uncompressed(b"\x03www\x07example\x00")
+            .write_to_string(&mut buf);
+        buf.push(' ');
+        name.parent().unwrap().write_to_string(&mut buf);
+        assert_eq!(buf, "www.example b.b.a");
+    }
+
+    #[test]
+    fn binary_labels() {
+        let buf = b"\x0eMy Printer \xe2\x84\xa2\x05a.b\\c\x02\x00\x01\x00";
+        let name = Name::scan(buf, buf).unwrap();
+        assert_eq!(name.byte_len(), 25);
+        assert_eq!(name.labels().next(), Some(&b"My Printer \xe2\x84\xa2"[..]));
+        assert_eq!(name.to_string(),
+            "My\\032Printer\\032\\226\\132\\162.a\\.b\\\\c.\\000\\001");
+        let owned = name.to_string().parse::<OwnedName>().unwrap();
+        assert_eq!(owned.as_bytes(), &buf[..]);
+
+        let buf = b"\x08\"(a);b)\"\x03@$x\x00";
+        let name = Name::scan(buf, buf).unwrap();
+        assert_eq!(name.to_string(), "\\\"\\(a\\)\\;b\\)\\\".\\@\\$x");
+        let owned = name.to_string().parse::<OwnedName>().unwrap();
+        assert_eq!(owned.as_bytes(), &buf[..]);
+    }
 }
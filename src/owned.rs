@@ -0,0 +1,567 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+use alloc::vec::Vec;
+
+use {Name, Packet, Question, ResourceRecord, RData, Header, Error};
+use {QueryType, QueryClass, Type, Class, RecordData, BuildError};
+use rdata::opt;
+
+/// A domain name which owns its data
+///
+/// The name is stored in wire format without compression pointers, so
+/// it doesn't depend on the packet it was parsed from.
+#[derive(Clone)]
+pub struct OwnedName {
+    bytes: Vec<u8>,
+}
+
+/// Record data which owns its data
+///
+/// Stores serialized record data with all domain names decompressed,
+/// use `as_rdata()` to get the parsed form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedRData {
+    typ: Type,
+    bytes: Vec<u8>,
+}
+
+/// An owned counterpart of `Question`
+#[derive(Debug, Clone)]
+#[allow(missing_docs)]  // fields are the same as in `Question`
+pub struct OwnedQuestion {
+    pub qname: OwnedName,
+    pub prefer_unicast: bool,
+    pub qtype: QueryType,
+    pub qclass: QueryClass,
+}
+
+/// An owned counterpart of `ResourceRecord`
+///
+/// Use `Builder::add_answer_record` and friends to write it into
+/// another packet.
+#[derive(Debug, Clone)]
+#[allow(missing_docs)]  // fields are the same as in `ResourceRecord`
+pub struct OwnedRecord {
+    pub name: OwnedName,
+    pub multicast_unique: bool,
+    pub cls: Class,
+    pub ttl: u32,
+    pub data: OwnedRData,
+}
+
+/// An owned counterpart of OPT pseudo-RR
+#[derive(Debug, Clone)]
+#[allow(missing_docs)]  // fields are the same as in `rdata::opt::Record`
+pub struct OwnedOpt {
+    pub udp: u16,
+    pub extrcode: u8,
+    pub version: u8,
+    pub flags: u16,
+    pub data: Vec<u8>,
+}
+
+/// Parsed DNS packet which doesn't borrow the receive buffer
+///
+/// Can be stored in caches and sent between threads. Use `as_packet()`
+/// to access records in parsed form.
+#[derive(Debug, Clone)]
+#[allow(missing_docs)]  // fields are the same as in `Packet`
+pub struct OwnedPacket {
+    pub header: Header,
+    pub questions: Vec<OwnedQuestion>,
+    pub answers: Vec<OwnedRecord>,
+    pub nameservers: Vec<OwnedRecord>,
+    pub additional: Vec<OwnedRecord>,
+    pub opt: Option<OwnedOpt>,
+}
+
+impl<'a> Name<'a> {
+    /// Copies the name out of the packet
+    pub fn into_owned(self) -> OwnedName {
+        OwnedName::from(self)
+    }
+}
+
+impl<'a> From<Name<'a>> for OwnedName {
+    fn from(name: Name<'a>) -> OwnedName {
+        let mut bytes = Vec::with_capacity(name.byte_len());
+        name.write_uncompressed(&mut bytes);
+        OwnedName { bytes }
+    }
+}
+
+impl OwnedName {
+    /// Returns the borrowed form of the name
+    pub fn as_name(&self) -> Name<'_> {
+        Name::from_uncompressed(&self.bytes)
+    }
+    /// Returns the name in wire format, without compression
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl FromStr for OwnedName {
+    type Err = Error;
+    /// Parses a name in presentation format
+    ///
+    /// Labels are separated by dots, the trailing dot is optional and
+    /// both an empty string and a single dot mean the root name. Dots
+    /// and backslashes inside labels may be escaped as `\.` and `\\`,
+    /// arbitrary bytes as `\DDD` where `DDD` is a decimal number.
+    fn from_str(s: &str) -> Result<OwnedName, Error> {
+        let mut bytes = Vec::with_capacity(s.len() + 2);
+        if s.is_empty() || s == "." {
+            bytes.push(0);
+            return Ok(OwnedName { bytes });
+        }
+        let mut chars = s.bytes();
+        let mut label_start = 0;
+        bytes.push(0);
+        loop {
+            let byte = match chars.next() {
+                Some(b'.') => {
+                    finish_label(&mut bytes, label_start)?;
+                    if chars.len() == 0 {
+                        break;
+                    }
+                    label_start = bytes.len();
+                    bytes.push(0);
+                    continue;
+                }
+                None => {
+                    finish_label(&mut bytes, label_start)?;
+                    break;
+                }
+                Some(b'\\') => parse_escape(&mut chars)?,
+                Some(c) => c,
+            };
+            bytes.push(byte);
+            if bytes.len() > 255 {
+                return Err(Error::NameTooLong);
+            }
+        }
+        bytes.push(0);
+        if bytes.len() > 255 {
+            return Err(Error::NameTooLong);
+        }
+        Ok(OwnedName { bytes })
+    }
+}
+
+/// Writes length of the label started at `start`, checking the limits
+fn finish_label(bytes: &mut [u8], start: usize) -> Result<(), Error> {
+    let len = bytes.len() - start - 1;
+    if len == 0 {
+        return Err(Error::EmptyLabel);
+    }
+    if len > 63 {
+        return Err(Error::LabelTooLong);
+    }
+    bytes[start] = len as u8;
+    Ok(())
+}
+
+/// Parses the part of escape sequence after the backslash
+fn parse_escape(chars: &mut ::core::str::Bytes) -> Result<u8, Error> {
+    match chars.next() {
+        Some(c @ b'0'..=b'9') => {
+            let mut value = (c - b'0') as u32;
+            for _ in 0..2 {
+                match chars.next() {
+                    Some(c @ b'0'..=b'9') => {
+                        value = value * 10 + (c - b'0') as u32;
+                    }
+                    _ => return Err(Error::BadEscape),
+                }
+            }
+            if value > 255 {
+                return Err(Error::BadEscape);
+            }
+            Ok(value as u8)
+        }
+        Some(c) => Ok(c),
+        None => Err(Error::BadEscape),
+    }
+}
+
+impl PartialEq for OwnedName {
+    fn eq(&self, other: &OwnedName) -> bool {
+        self.as_name() == other.as_name()
+    }
+}
+
+impl Eq for OwnedName {}
+
+impl Hash for OwnedName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_name().hash(state)
+    }
+}
+
+impl Ord for OwnedName {
+    fn cmp(&self, other: &OwnedName) -> Ordering {
+        self.as_name().cmp(&other.as_name())
+    }
+}
+
+impl PartialOrd for OwnedName {
+    fn partial_cmp(&self, other: &OwnedName) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for OwnedName {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.as_name(), fmt)
+    }
+}
+
+impl fmt::Debug for OwnedName {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_tuple("OwnedName")
+        .field(&format!("{}", self))
+        .finish()
+    }
+}
+
+impl<'a> RData<'a> {
+    /// Copies the record data out of the packet
+    ///
+    /// Fails only if the record was constructed manually with
+    /// a character string longer than 255 bytes.
+    pub fn into_owned(self) -> Result<OwnedRData, BuildError> {
+        let mut bytes = Vec::new();
+        self.write(&mut bytes)?;
+        Ok(OwnedRData {
+            typ: self.type_code(),
+            bytes,
+        })
+    }
+}
+
+impl OwnedRData {
+    /// Returns the type of the record
+    pub fn type_code(&self) -> Type {
+        self.typ
+    }
+    /// Returns record data in wire format, without name compression
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+    /// Returns record data in parsed form
+    ///
+    /// Fails if the data doesn't match the record type, which is only
+    /// possible when it was copied from manually constructed
+    /// `RData::Unknown`.
+    pub fn as_rdata(&self) -> Result<RData<'_>, Error> {
+        RData::parse(self.typ, &self.bytes, &self.bytes)
+    }
+    /// Returns record data suitable for adding to the `Builder`
+    pub fn as_record_data(&self) -> RecordData<'_> {
+        RecordData::Raw(self.typ, &self.bytes)
+    }
+}
+
+impl<'a> Question<'a> {
+    /// Copies the question out of the packet
+    pub fn into_owned(self) -> OwnedQuestion {
+        OwnedQuestion {
+            qname: self.qname.into_owned(),
+            prefer_unicast: self.prefer_unicast,
+            qtype: self.qtype,
+            qclass: self.qclass,
+        }
+    }
+}
+
+impl OwnedQuestion {
+    /// Returns the borrowed form of the question
+    pub fn as_question(&self) -> Question<'_> {
+        Question {
+            qname: self.qname.as_name(),
+            prefer_unicast: self.prefer_unicast,
+            qtype: self.qtype,
+            qclass: self.qclass,
+        }
+    }
+}
+
+impl<'a> ResourceRecord<'a> {
+    /// Copies the record out of the packet
+    ///
+    /// See `RData::into_owned` for possible errors.
+    pub fn into_owned(self) -> Result<OwnedRecord, BuildError> {
+        Ok(OwnedRecord {
+            name: self.name.into_owned(),
+            multicast_unique: self.multicast_unique,
+            cls: self.cls,
+            ttl: self.ttl,
+            data: self.data.into_owned()?,
+        })
+    }
+}
+
+impl OwnedRecord {
+    /// Returns the borrowed form of the record
+    ///
+    /// See `OwnedRData::as_rdata` for possible errors.
+    pub fn as_record(&self) -> Result<ResourceRecord<'_>, Error> {
+        Ok(ResourceRecord {
+            name: self.name.as_name(),
+            multicast_unique: self.multicast_unique,
+            cls: self.cls,
+            ttl: self.ttl,
+            data: self.data.as_rdata()?,
+        })
+    }
+}
+
+impl<'a> opt::Record<'a> {
+    /// Copies the OPT record out of the packet
+    pub fn into_owned(self) -> OwnedOpt {
+        OwnedOpt {
+            udp: self.udp,
+            extrcode: self.extrcode,
+            version: self.version,
+            flags: self.flags,
+            data: self.data.to_vec(),
+        }
+    }
+}
+
+impl OwnedOpt {
+    /// Returns the borrowed form of the OPT record
+    pub fn as_opt(&self) -> opt::Record<'_> {
+        opt::Record {
+            udp: self.udp,
+            extrcode: self.extrcode,
+            version: self.version,
+            flags: self.flags,
+            data: &self.data,
+        }
+    }
+}
+
+impl<'a> Packet<'a> {
+    /// Copies the whole packet so it doesn't borrow the original buffer
+    ///
+    /// Never fails for a parsed packet, see `RData::into_owned` for
+    /// the errors of manually constructed records.
+    pub fn into_owned(self) -> Result<OwnedPacket, BuildError> {
+        Ok(OwnedPacket {
+            header: self.header,
+            questions: self.questions.into_iter()
+                .map(Question::into_owned).collect(),
+            answers: self.answers.into_iter()
+                .map(ResourceRecord::into_owned).collect::<Result<_, _>>()?,
+            nameservers: self.nameservers.into_iter()
+                .map(ResourceRecord::into_owned).collect::<Result<_, _>>()?,
+            additional: self.additional.into_iter()
+                .map(ResourceRecord::into_owned).collect::<Result<_, _>>()?,
+            opt: self.opt.map(opt::Record::into_owned),
+        })
+    }
+}
+
+impl OwnedPacket {
+    /// Returns the borrowed form of the packet
+    ///
+    /// Record data is parsed again on each call. Never fails for a packet
+    /// copied from a parsed one, see `OwnedRData::as_rdata`.
+    pub fn as_packet(&self) -> Result<Packet<'_>, Error> {
+        Ok(Packet {
+            header: self.header,
+            questions: self.questions.iter()
+                .map(OwnedQuestion::as_question).collect(),
+            answers: self.answers.iter()
+                .map(OwnedRecord::as_record).collect::<Result<_, _>>()?,
+            nameservers: self.nameservers.iter()
+                .map(OwnedRecord::as_record).collect::<Result<_, _>>()?,
+            additional: self.additional.iter()
+                .map(OwnedRecord::as_record).collect::<Result<_, _>>()?,
+            opt: self.opt.as_ref().map(OwnedOpt::as_opt),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::Ipv4Addr;
+
+    use {Packet, Builder, RData, Type, Error, BuildError};
+    use rdata::Hinfo;
+    use QueryType as QT;
+    use Class as C;
+    use super::*;
+
+    fn parse_owned(data: &[u8]) -> OwnedPacket {
+        let data = data.to_vec();
+        Packet::parse(&data).unwrap().into_owned().unwrap()
+    }
+
+    #[test]
+    fn owned_packet() {
+        let packet = parse_owned(b"\x4a\xf0\x81\x80\x00\x01\x00\x01\x00\x01\x00\x01\
+                       \x03www\x05skype\x03com\x00\x00\x01\x00\x01\
+                       \xc0\x0c\x00\x05\x00\x01\x00\x00\x0e\x10\
+                       \x00\x1c\x07livecms\x0etrafficmanager\x03net\x00\
+                       \xc0\x42\x00\x02\x00\x01\x00\x01\xd5\xd3\x00\x11\
+                       \x01g\x0cgtld-servers\xc0\x42\
+                       \x01a\xc0\x55\x00\x01\x00\x01\x00\x00\xa3\x1c\
+                       \x00\x04\xc0\x05\x06\x1e");
+        assert_eq!(packet.header.id, 19184);
+        assert_eq!(&packet.questions[0].qname.to_string()[..],
+            "www.skype.com");
+        assert_eq!(packet.answers[0].data.type_code(), Type::CNAME);
+        // name pointing into the other record is decompressed
+        assert_eq!(packet.additional[0].name.as_bytes(),
+            b"\x01a\x0cgtld-servers\x03net\x00");
+        assert_eq!(packet.nameservers[0].data.as_bytes(),
+            b"\x01g\x0cgtld-servers\x03net\x00");
+
+        let view = packet.as_packet().unwrap();
+        assert_eq!(view.questions[0].qtype, QT::A);
+        match view.nameservers[0].data {
+            RData::NS(ns) => {
+                assert_eq!(&ns.0.to_string()[..], "g.gtld-servers.net");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+        match view.additional[0].data {
+            RData::A(addr) => {
+                assert_eq!(addr.0, Ipv4Addr::new(192, 5, 6, 30));
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+
+    #[test]
+    fn owned_opt() {
+        let packet = parse_owned(b"\x95\xce\x01\x00\x00\x01\x00\x00\x00\x00\x00\x01\
+            \x06google\x03com\x00\x00\x01\x00\x01\
+            \x00\x00\x29\x10\x00\x00\x00\x80\x00\x00\x0c\
+            \x00\x0a\x00\x08\x01\x02\x03\x04\x05\x06\x07\x08");
+        let opt = packet.opt.as_ref().unwrap().as_opt();
+        assert_eq!(opt.udp, 4096);
+        assert!(opt.dnssec_ok());
+        assert_eq!(opt.options().count(), 1);
+    }
+
+    #[test]
+    fn owned_to_builder() {
+        let packet = parse_owned(b"\x06%\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x07example\x03com\x00\x00\x0f\x00\x01\
+            \xc0\x0c\x00\x0f\x00\x01\x00\x00\x0e\x10\x00\x09\x00\x0a\
+            \x04mail\xc0\x0c");
+        let query = packet.as_packet().unwrap();
+        let mut bld = Builder::new_response(&query);
+        for rr in &packet.answers {
+            bld.add_answer(&rr.name.to_string(), rr.cls, rr.ttl,
+                &rr.data.as_record_data());
+        }
+        let data = bld.build().unwrap();
+        let copy = Packet::parse(&data).unwrap();
+        assert_eq!(copy.answers.len(), 1);
+        assert_eq!(copy.answers[0].cls, C::IN);
+        match copy.answers[0].data {
+            RData::MX(mx) => {
+                assert_eq!(mx.preference, 10);
+                assert_eq!(&mx.exchange.to_string()[..], "mail.example.com");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+
+    #[test]
+    fn owned_to_builder_without_strings() {
+        // binary label with a dot, a backslash and a zero byte
+        let packet = parse_owned(b"\x06%\x81\x80\x00\x01\x00\x01\x00\x00\x00\x01\
+            \x05a.\\\x00b\x07example\x00\x00\x0f\x00\x01\
+            \xc0\x0c\x00\x0f\x80\x01\x00\x00\x0e\x10\x00\x09\x00\x0a\
+            \x04mail\xc0\x0c\
+            \x04mail\xc0\x0c\x00\x01\x00\x01\x00\x00\x0e\x10\x00\x04\
+            \xc0\x00\x02\x01");
+        let mut bld = Builder::new_query(1, false);
+        let q = &packet.questions[0];
+        bld.add_question_name(&q.qname.as_name(), q.prefer_unicast,
+                              q.qtype, q.qclass);
+        bld.add_answer_record(&packet.answers[0]);
+        bld.add_additional_record(&packet.additional[0]);
+        assert_matches!(bld.try_add_nameserver_record(&packet.answers[0]),
+                        Err(BuildError::SectionOrder));
+        let data = bld.build().unwrap();
+        // owner names are compressed, record data is not
+        assert_eq!(data.len(), 12 + 19 + 34 + 21);
+        let copy = Packet::parse(&data).unwrap().into_owned().unwrap();
+        assert_eq!(copy.questions[0].qname.as_bytes(),
+                   b"\x05a.\\\x00b\x07example\x00");
+        assert_eq!(copy.answers[0].name, packet.answers[0].name);
+        assert!(copy.answers[0].multicast_unique);
+        assert_eq!(copy.answers[0].data, packet.answers[0].data);
+        assert_eq!(copy.additional[0].name.as_bytes(),
+                   b"\x04mail\x05a.\\\x00b\x07example\x00");
+        assert_eq!(copy.additional[0].data, packet.additional[0].data);
+    }
+
+    #[test]
+    fn invalid_rdata() {
+        let cpu = [b'x'; 256];
+        let hinfo = RData::HINFO(Hinfo { cpu: &cpu, os: b"linux" });
+        assert_matches!(hinfo.into_owned(), Err(BuildError::StringTooLong));
+        let hinfo = RData::HINFO(Hinfo { cpu: &cpu[..255], os: b"linux" });
+        assert_eq!(hinfo.into_owned().unwrap().as_bytes().len(), 262);
+        let bad = RData::Unknown(Type::A, b"\x01\x02\x03").into_owned().unwrap();
+        assert_matches!(bad.as_rdata(), Err(Error::WrongRdataLength));
+    }
+
+    #[test]
+    fn owned_is_static() {
+        fn assert_send_static<T: Send + 'static>(_: &T) {}
+        let packet = parse_owned(b"\x06%\x01\x00\x00\x01\x00\x00\x00\x00\x00\x00\
+            \x07example\x03com\x00\x00\x01\x00\x01");
+        assert_send_static(&packet);
+    }
+
+    #[test]
+    fn name_from_str() {
+        let name = "www.example.com".parse::<OwnedName>().unwrap();
+        assert_eq!(name.as_bytes(), b"\x03www\x07example\x03com\x00");
+        let name = "www.example.com.".parse::<OwnedName>().unwrap();
+        assert_eq!(name.as_bytes(), b"\x03www\x07example\x03com\x00");
+        assert_eq!("".parse::<OwnedName>().unwrap().as_bytes(), b"\x00");
+        assert_eq!(".".parse::<OwnedName>().unwrap().as_bytes(), b"\x00");
+        let name = r"a\.b\\c.\065\000".parse::<OwnedName>().unwrap();
+        assert_eq!(name.as_bytes(), b"\x05a.b\\c\x02A\x00\x00");
+        // non-ASCII characters are kept as UTF-8 bytes
+        let name = "\u{44d}.example".parse::<OwnedName>().unwrap();
+        assert_eq!(name.as_bytes(), b"\x02\xd1\x8d\x07example\x00");
+    }
+
+    #[test]
+    fn name_limits() {
+        let label = "x".repeat(63);
+        let name = label.parse::<OwnedName>().unwrap();
+        assert_eq!(name.as_bytes().len(), 65);
+        assert_matches!("x".repeat(64).parse::<OwnedName>(),
+                        Err(Error::LabelTooLong));
+        // 4 labels of 63 bytes plus length bytes and root is 257 bytes
+        let name = [&label[..]; 4].join(".");
+        assert_matches!(name.parse::<OwnedName>(), Err(Error::NameTooLong));
+        // 3 labels of 63 bytes and 1 of 61 bytes is exactly 255 bytes
+        let name = format!("{0}.{0}.{0}.{1}", label, "x".repeat(61));
+        assert_eq!(name.parse::<OwnedName>().unwrap().as_bytes().len(), 255);
+    }
+
+    #[test]
+    fn invalid_names() {
+        assert_matches!("a..b".parse::<OwnedName>(), Err(Error::EmptyLabel));
+        assert_matches!(".a".parse::<OwnedName>(), Err(Error::EmptyLabel));
+        assert_matches!("a..".parse::<OwnedName>(), Err(Error::EmptyLabel));
+        assert_matches!("a\\".parse::<OwnedName>(), Err(Error::BadEscape));
+        assert_matches!("a\\25".parse::<OwnedName>(), Err(Error::BadEscape));
+        assert_matches!("a\\256".parse::<OwnedName>(), Err(Error::BadEscape));
+    }
+}
@@ -1,4 +1,5 @@
-use std::i32;
+use core::i32;
+use alloc::vec::Vec;
 
 use byteorder::{BigEndian, ByteOrder};
 
@@ -16,25 +17,7 @@ impl<'a> Packet<'a> {
         let mut offset = Header::size();
         let mut questions = Vec::with_capacity(header.questions as usize);
         for _ in 0..header.questions {
-            let name = try!(Name::scan(&data[offset..], data));
-            offset += name.byte_len();
-            if offset + 4 > data.len() {
-                return Err(Error::UnexpectedEOF);
-            }
-            let qtype = try!(QueryType::parse(
-                BigEndian::read_u16(&data[offset..offset+2])));
-            offset += 2;
-
-            let (prefer_unicast, qclass) = try!(parse_qclass_code(
-                BigEndian::read_u16(&data[offset..offset+2])));
-            offset += 2;
-
-            questions.push(Question {
-                qname: name,
-                qtype: qtype,
-                prefer_unicast: prefer_unicast,
-                qclass: qclass,
-            });
+            questions.push(try!(parse_question(data, &mut offset)));
         }
         let mut answers = Vec::with_capacity(header.answers as usize);
         for _ in 0..header.answers {
@@ -47,7 +30,7 @@ impl<'a> Packet<'a> {
         let mut additional = Vec::with_capacity(header.additional as usize);
         let mut opt = None;
         for _ in 0..header.additional {
-            if offset + 3 <= data.len() && data[offset..offset+3] == OPT_RR_START {
+            if is_opt_record(data, offset) {
                 if opt.is_none() {
                     opt = Some(try!(parse_opt_record(data, &mut offset)));
                 } else {
@@ -68,6 +51,85 @@ impl<'a> Packet<'a> {
     }
 }
 
+pub(crate) fn parse_question<'a>(data: &'a [u8], offset: &mut usize)
+    -> Result<Question<'a>, Error>
+{
+    let name = Name::scan(&data[*offset..], data)?;
+    *offset += name.byte_len();
+    if *offset + 4 > data.len() {
+        return Err(Error::UnexpectedEOF);
+    }
+    let qtype = QueryType::parse(
+        BigEndian::read_u16(&data[*offset..*offset+2]))?;
+    *offset += 2;
+
+    let (prefer_unicast, qclass) = parse_qclass_code(
+        BigEndian::read_u16(&data[*offset..*offset+2]))?;
+    *offset += 2;
+
+    Ok(Question {
+        qname: name,
+        qtype: qtype,
+        prefer_unicast: prefer_unicast,
+        qclass: qclass,
+    })
+}
+
+/// Returns true if the record at `offset` is an OPT pseudo-RR
+pub(crate) fn is_opt_record(data: &[u8], offset: usize) -> bool {
+    offset + 3 <= data.len() && data[offset..offset+3] == OPT_RR_START
+}
+
+/// Returns offset past the name at `offset`, not following pointers
+fn skip_name(data: &[u8], mut offset: usize) -> Result<usize, Error> {
+    loop {
+        let byte = *data.get(offset).ok_or(Error::UnexpectedEOF)?;
+        if byte & 0b1100_0000 == 0b1100_0000 {
+            offset += 2;
+            break;
+        } else if byte & 0b1100_0000 != 0 {
+            return Err(Error::UnknownLabelFormat);
+        } else if byte == 0 {
+            offset += 1;
+            break;
+        }
+        offset += byte as usize + 1;
+    }
+    if offset > data.len() {
+        return Err(Error::UnexpectedEOF);
+    }
+    Ok(offset)
+}
+
+/// Skips a question without decoding it
+pub(crate) fn skip_question(data: &[u8], offset: &mut usize)
+    -> Result<(), Error>
+{
+    let end = skip_name(data, *offset)? + 4;
+    if end > data.len() {
+        return Err(Error::UnexpectedEOF);
+    }
+    *offset = end;
+    Ok(())
+}
+
+/// Skips a resource record without decoding its name and data
+pub(crate) fn skip_record(data: &[u8], offset: &mut usize)
+    -> Result<(), Error>
+{
+    let pos = skip_name(data, *offset)?;
+    if pos + 10 > data.len() {
+        return Err(Error::UnexpectedEOF);
+    }
+    let rdlen = BigEndian::read_u16(&data[pos+8..pos+10]) as usize;
+    let end = pos + 10 + rdlen;
+    if end > data.len() {
+        return Err(Error::UnexpectedEOF);
+    }
+    *offset = end;
+    Ok(())
+}
+
 fn parse_qclass_code(value: u16) -> Result<(bool, QueryClass), Error> {
     let prefer_unicast = value & 0x8000 == 0x8000;
     let qclass_code = value & 0x7FFF;
@@ -85,7 +147,7 @@ fn parse_class_code(value: u16) -> Result<(bool, Class), Error> {
 }
 
 // Generic function to parse answer, nameservers, and additional records.
-fn parse_record<'a>(data: &'a [u8], offset: &mut usize) -> Result<ResourceRecord<'a>, Error> {
+pub(crate) fn parse_record<'a>(data: &'a [u8], offset: &mut usize) -> Result<ResourceRecord<'a>, Error> {
     let name = try!(Name::scan(&data[*offset..], data));
     *offset += name.byte_len();
     if *offset + 10 > data.len() {
@@ -122,7 +184,7 @@ fn parse_record<'a>(data: &'a [u8], offset: &mut usize) -> Result<ResourceRecord
 }
 
 // Function to parse an RFC 6891 OPT Pseudo RR
-fn parse_opt_record<'a>(data: &'a [u8], offset: &mut usize) -> Result<Opt<'a>, Error> {
+pub(crate) fn parse_opt_record<'a>(data: &'a [u8], offset: &mut usize) -> Result<Opt<'a>, Error> {
     if *offset + 11 > data.len() {
         return Err(Error::UnexpectedEOF);
     }
@@ -130,7 +192,7 @@ fn parse_opt_record<'a>(data: &'a [u8], offset: &mut usize) -> Result<Opt<'a>, E
     let typ = try!(Type::parse(
         BigEndian::read_u16(&data[*offset..*offset+2])));
     if typ != Type::OPT {
-        return Err(Error::InvalidType(typ as u16));
+        return Err(Error::InvalidType(typ.into()));
     }
     *offset += 2;
     let udp = BigEndian::read_u16(&data[*offset..*offset+2]);
@@ -146,8 +208,8 @@ fn parse_opt_record<'a>(data: &'a [u8], offset: &mut usize) -> Result<Opt<'a>, E
     if *offset + rdlen > data.len() {
         return Err(Error::UnexpectedEOF);
     }
-    let data = try!(RData::parse(typ,
-        &data[*offset..*offset+rdlen], data));
+    let data = &data[*offset..*offset+rdlen];
+    try!(Opt::check_options(data));
     *offset += rdlen;
 
     Ok(Opt {
@@ -169,7 +231,7 @@ mod test {
     use QueryType as QT;
     use QueryClass as QC;
     use Class as C;
-    use RData;
+    use {RData, Type};
 
     #[test]
     fn parse_example_query() {
@@ -452,4 +514,33 @@ mod test {
             None => panic!("Missing OPT RR")
         }
     }
+
+    #[test]
+    fn parse_unknown_type_and_class() {
+        let response = b"\x06%\x81\x80\x00\x01\x00\x02\x00\x00\x00\x00\
+                         \x07example\x03com\x00\xff\x00\x00\xfe\
+                         \xc0\x0c\xff\x00\x00\x01\x00\x00\x0e\x10\x00\x16\
+                         \x00\x05issueletsencrypt.org\
+                         \xc0\x0c\x00\x01\x00\x2a\x00\x00\x0e\x10\x00\x04\
+                         \x7f\x00\x00\x01";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::Unknown(65280));
+        assert_eq!(packet.questions[0].qclass, QC::Unknown(254));
+        assert_eq!(packet.answers.len(), 2);
+        match packet.answers[0].data {
+            RData::Unknown(typ, data) => {
+                assert_eq!(typ, Type::Unknown(65280));
+                assert_eq!(u16::from(typ), 65280);
+                assert_eq!(data, b"\x00\x05issueletsencrypt.org");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+        assert_eq!(packet.answers[1].cls, C::Unknown(42));
+        match packet.answers[1].data {
+            RData::A(addr) => {
+                assert_eq!(addr.0, Ipv4Addr::new(127, 0, 0, 1));
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use std::net::Ipv4Addr;
+use net::Ipv4Addr;
 
 use Error;
 use byteorder::{BigEndian, ByteOrder};
@@ -1,4 +1,4 @@
-use std::net::Ipv6Addr;
+use net::Ipv6Addr;
 
 use Error;
 use byteorder::{BigEndian, ByteOrder};
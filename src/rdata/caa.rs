@@ -0,0 +1,85 @@
+use core::str::from_utf8;
+
+use Error;
+
+/// The CAA (Certification Authority Authorization) record (RFC 8659)
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub flags: u8,
+    /// Property tag, e.g. `issue`, `issuewild` or `iodef`
+    pub tag: &'a str,
+    /// Property value, its format depends on the tag
+    pub value: &'a [u8],
+}
+
+impl<'a> Record<'a> {
+    /// Returns true if the Issuer Critical flag is set
+    ///
+    /// A CA must not issue certificates if it doesn't understand the
+    /// tag of a critical property.
+    pub fn is_critical(&self) -> bool {
+        self.flags & 0x80 != 0
+    }
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 257;
+
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        if rdata.len() < 2 {
+            return Err(Error::WrongRdataLength);
+        }
+        let tag_len = rdata[1] as usize;
+        if tag_len == 0 || rdata.len() < 2 + tag_len {
+            return Err(Error::WrongRdataLength);
+        }
+        let tag = &rdata[2..2+tag_len];
+        if !tag.iter().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(Error::InvalidCaaTag);
+        }
+        let record = Record {
+            flags: rdata[0],
+            tag: from_utf8(tag).unwrap(),
+            value: &rdata[2+tag_len..],
+        };
+        Ok(super::RData::CAA(record))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\xaa\xbb\x81\x80\x00\x01\x00\x02\x00\x00\x00\x00\
+            \x07example\x03com\x00\x01\x01\x00\x01\
+            \xc0\x0c\x01\x01\x00\x01\x00\x00\x0e\x10\x00\x16\
+            \x00\x05issueletsencrypt.org\
+            \xc0\x0c\x01\x01\x00\x01\x00\x00\x0e\x10\x00\x1c\
+            \x80\x05iodefmailto:ca@example.com";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::CAA);
+        assert_eq!(packet.answers.len(), 2);
+        match packet.answers[0].data {
+            RData::CAA(caa) => {
+                assert!(!caa.is_critical());
+                assert_eq!(caa.tag, "issue");
+                assert_eq!(caa.value, b"letsencrypt.org");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+        match packet.answers[1].data {
+            RData::CAA(caa) => {
+                assert!(caa.is_critical());
+                assert_eq!(caa.tag, "iodef");
+                assert_eq!(caa.value, b"mailto:ca@example.com");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use super::dnskey;
+
+/// The CDNSKEY (Child DNSKEY) record (RFC 7344), it has the same format
+/// as DNSKEY
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a>(pub dnskey::Record<'a>);
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 60;
+
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        let record = Record(dnskey::Record::parse_fields(rdata)?);
+        Ok(super::RData::CDNSKEY(record))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\x124\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x05dskey\x07example\x03com\x00\x00<\x00\x01\
+            \xc0\x0c\x00<\x00\x01\x00\x01Q\x80\x00\
+            \x86\x01\x01\x03\x05\x01\x03\x9e\x8a$t\x18\xe3\x18\x90;!Z\x84\
+            \x8a\xcf\xd5\xf3\x7f\x02k\xd4\x06-\xb2lwLi\x09h\xd5\xd5m\xf8\xbf\
+            \xda\x91\xe6\xf3m\x9a'\x98\x88\xf4\x1335|^`)\x99\x0d\x10\xfd\xf5\
+            f0b\xa5\x12v3&\x98\x0aa]\xdb\xf1z\x05\xdd\xfc\xce~_\xb3\xab\xcc\
+            \xa0Z1\xb0\x95tR\xd4R\x1e\x83\x87\x07\x89\x061\x15\xbf\x97\xf6\
+            \xc3\x08\xcc\xf5|\xdc\x9c\xe7\xfe\x10\xf6\xed\x1b\xd0\xcc\x06`\
+            \x03\x8cP\xdc\xdb\x0f\xeb\x96</\x17";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::CDNSKEY);
+        match packet.answers[0].data {
+            RData::CDNSKEY(key) => {
+                assert_eq!(key.0.flags, 257);
+                assert!(key.0.is_secure_entry_point());
+                assert_eq!(key.0.key_tag(), 60486);
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+}
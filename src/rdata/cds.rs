@@ -0,0 +1,42 @@
+use super::ds;
+
+/// The CDS (Child DS) record (RFC 7344), it has the same format as DS
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a>(pub ds::Record<'a>);
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 59;
+
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        let record = Record(ds::Record::parse_fields(rdata)?);
+        Ok(super::RData::CDS(record))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\x124\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x05dskey\x07example\x03com\x00\x00;\x00\x01\
+            \xc0\x0c\x00;\x00\x01\x00\x01Q\x80\x00\x18\xecE\x05\x01\
+            +\xb1\x83\xaf_\"X\x81y\xa5;\x0a\x98c\x1f\xad\x1a)!\x18";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::CDS);
+        match packet.answers[0].data {
+            RData::CDS(cds) => {
+                assert_eq!(cds.0.key_tag, 60485);
+                assert_eq!(cds.0.algorithm, 5);
+                assert_eq!(cds.0.digest_type, 1);
+                assert_eq!(cds.0.digest.len(), 20);
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use core::fmt;
+
+use Name;
+
+/// The DNAME (delegation name) record (RFC 6672)
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a>(pub Name<'a>);
+
+impl<'a> fmt::Display for Record<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(fmt)
+    }
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 39;
+
+    fn parse(rdata: &'a [u8], original: &'a [u8]) -> super::RDataResult<'a> {
+        let name = Name::scan(rdata, original)?;
+        let record = Record(name);
+        Ok(super::RData::DNAME(record))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\xaa\xbb\x81\x80\x00\x01\x00\x02\x00\x00\x00\x00\
+            \x03www\x03old\x07example\x03com\x00\x00\x01\x00\x01\
+            \xc0\x10\x00\x27\x00\x01\x00\x00\x0e\x10\x00\x06\
+            \x03new\xc0\x14\
+            \xc0\x0c\x00\x05\x00\x01\x00\x00\x0e\x10\x00\x06\
+            \x03www\xc0\x31";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::A);
+        assert_eq!(&packet.answers[0].name.to_string()[..],
+            "old.example.com");
+        match packet.answers[0].data {
+            RData::DNAME(dname) => {
+                assert_eq!(&dname.to_string()[..], "new.example.com");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+        match packet.answers[1].data {
+            RData::CNAME(cname) => {
+                assert_eq!(&cname.to_string()[..], "www.new.example.com");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use Error;
+use byteorder::{BigEndian, ByteOrder};
+
+/// The DNSKEY record (RFC 4034)
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub flags: u16,
+    pub protocol: u8,
+    pub algorithm: u8,
+    pub public_key: &'a [u8],
+}
+
+const ZONE_KEY: u16 = 0x0100;
+const REVOKE: u16 = 0x0080;
+const SECURE_ENTRY_POINT: u16 = 0x0001;
+
+impl<'a> Record<'a> {
+    pub(crate) fn parse_fields(rdata: &'a [u8]) -> Result<Record<'a>, Error> {
+        if rdata.len() < 4 {
+            return Err(Error::WrongRdataLength);
+        }
+        Ok(Record {
+            flags: BigEndian::read_u16(&rdata[..2]),
+            protocol: rdata[2],
+            algorithm: rdata[3],
+            public_key: &rdata[4..],
+        })
+    }
+    /// The key may be used to verify zone data
+    pub fn is_zone_key(&self) -> bool {
+        self.flags & ZONE_KEY != 0
+    }
+    /// The key is revoked (RFC 5011)
+    pub fn is_revoked(&self) -> bool {
+        self.flags & REVOKE != 0
+    }
+    /// The key is intended to be a key signing key
+    pub fn is_secure_entry_point(&self) -> bool {
+        self.flags & SECURE_ENTRY_POINT != 0
+    }
+    /// Calculates key tag as described in RFC 4034 Appendix B
+    ///
+    /// Obsolete algorithm 1 (RSA/MD5) is not supported, i.e. the tag
+    /// will not match the real one.
+    pub fn key_tag(&self) -> u16 {
+        let mut acc = self.flags as u32;
+        acc += ((self.protocol as u32) << 8) | self.algorithm as u32;
+        for (i, &byte) in self.public_key.iter().enumerate() {
+            if i & 1 == 0 {
+                acc += (byte as u32) << 8;
+            } else {
+                acc += byte as u32;
+            }
+        }
+        acc += (acc >> 16) & 0xFFFF;
+        (acc & 0xFFFF) as u16
+    }
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 48;
+
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        Ok(super::RData::DNSKEY(Record::parse_fields(rdata)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\x124\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x05dskey\x07example\x03com\x00\x000\x00\x01\
+            \xc0\x0c\x000\x00\x01\x00\x01Q\x80\x00\
+            \x86\x01\x00\x03\x05\x01\x03\x9e\x8a$t\x18\xe3\x18\x90;!Z\x84\
+            \x8a\xcf\xd5\xf3\x7f\x02k\xd4\x06-\xb2lwLi\x09h\xd5\xd5m\xf8\xbf\
+            \xda\x91\xe6\xf3m\x9a'\x98\x88\xf4\x1335|^`)\x99\x0d\x10\xfd\xf5\
+            f0b\xa5\x12v3&\x98\x0aa]\xdb\xf1z\x05\xdd\xfc\xce~_\xb3\xab\xcc\
+            \xa0Z1\xb0\x95tR\xd4R\x1e\x83\x87\x07\x89\x061\x15\xbf\x97\xf6\
+            \xc3\x08\xcc\xf5|\xdc\x9c\xe7\xfe\x10\xf6\xed\x1b\xd0\xcc\x06`\
+            \x03\x8cP\xdc\xdb\x0f\xeb\x96</\x17";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::DNSKEY);
+        match packet.answers[0].data {
+            RData::DNSKEY(key) => {
+                assert_eq!(key.flags, 256);
+                assert_eq!(key.protocol, 3);
+                assert_eq!(key.algorithm, 5);
+                assert_eq!(key.public_key.len(), 130);
+                assert!(key.is_zone_key());
+                assert!(!key.is_secure_entry_point());
+                assert!(!key.is_revoked());
+                // Key tag from RFC 4034 Section 5.4
+                assert_eq!(key.key_tag(), 60485);
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+}
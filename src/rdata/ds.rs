@@ -0,0 +1,65 @@
+use Error;
+use byteorder::{BigEndian, ByteOrder};
+
+/// The DS (Delegation Signer) record (RFC 4034)
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub key_tag: u16,
+    pub algorithm: u8,
+    pub digest_type: u8,
+    pub digest: &'a [u8],
+}
+
+impl<'a> Record<'a> {
+    pub(crate) fn parse_fields(rdata: &'a [u8]) -> Result<Record<'a>, Error> {
+        if rdata.len() < 5 {
+            return Err(Error::WrongRdataLength);
+        }
+        Ok(Record {
+            key_tag: BigEndian::read_u16(&rdata[..2]),
+            algorithm: rdata[2],
+            digest_type: rdata[3],
+            digest: &rdata[4..],
+        })
+    }
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 43;
+
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        Ok(super::RData::DS(Record::parse_fields(rdata)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\x124\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x05dskey\x07example\x03com\x00\x00+\x00\x01\
+            \xc0\x0c\x00+\x00\x01\x00\x01Q\x80\x00\x18\xecE\x05\x01\
+            +\xb1\x83\xaf_\"X\x81y\xa5;\x0a\x98c\x1f\xad\x1a)!\x18";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::DS);
+        assert_eq!(packet.answers.len(), 1);
+        assert_eq!(&packet.answers[0].name.to_string()[..],
+            "dskey.example.com");
+        match packet.answers[0].data {
+            RData::DS(ds) => {
+                assert_eq!(ds.key_tag, 60485);
+                assert_eq!(ds.algorithm, 5);
+                assert_eq!(ds.digest_type, 1);
+                assert_eq!(ds.digest, &b"+\xb1\x83\xaf_\"X\x81y\xa5;\x0a\
+                    \x98c\x1f\xad\x1a)!\x18"[..]);
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+}
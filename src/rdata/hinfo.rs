@@ -1,11 +1,57 @@
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct Record;
+use Error;
 
-impl<'a> super::Record<'a> for Record {
+/// The HINFO (host information) record
+///
+/// Both fields are character-strings, usually ASCII
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub cpu: &'a [u8],
+    pub os: &'a [u8],
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
 
     const TYPE: isize = 13;
 
-    fn parse(_rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
-        unimplemented!();
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        if rdata.is_empty() {
+            return Err(Error::WrongRdataLength);
+        }
+        let cpu_end = 1 + rdata[0] as usize;
+        if rdata.len() <= cpu_end ||
+            rdata.len() != cpu_end + 1 + rdata[cpu_end] as usize
+        {
+            return Err(Error::WrongRdataLength);
+        }
+        let record = Record {
+            cpu: &rdata[1..cpu_end],
+            os: &rdata[cpu_end+1..],
+        };
+        Ok(super::RData::HINFO(record))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\xaa\xbb\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x07example\x03com\x00\x00\x0d\x00\x01\
+            \xc0\x0c\x00\x0d\x00\x01\x00\x00\x0e\x10\x00\x09\
+            \x07RFC8482\x00";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::HINFO);
+        match packet.answers[0].data {
+            RData::HINFO(hinfo) => {
+                assert_eq!(hinfo.cpu, b"RFC8482");
+                assert_eq!(hinfo.os, b"");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
     }
 }
@@ -0,0 +1,18 @@
+use super::svcb;
+
+/// The HTTPS record (RFC 9460)
+///
+/// It has the same format as the SVCB record and is used for services
+/// reachable over HTTPS.
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a>(pub svcb::Record<'a>);
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 65;
+
+    fn parse(rdata: &'a [u8], original: &'a [u8]) -> super::RDataResult<'a> {
+        let record = svcb::Record::parse_fields(rdata, original)?;
+        Ok(super::RData::HTTPS(Record(record)))
+    }
+}
@@ -0,0 +1,124 @@
+use {Type, Error};
+use byteorder::{BigEndian, ByteOrder};
+
+/// The LOC (location information) record (RFC 1876)
+///
+/// Fields are kept in wire format, use methods to get values in meters
+/// and degrees.
+#[derive(Debug, Clone, Copy)]
+pub struct Record {
+    pub version: u8,
+    pub size: u8,
+    pub horiz_pre: u8,
+    pub vert_pre: u8,
+    pub latitude: u32,
+    pub longitude: u32,
+    pub altitude: u32,
+}
+
+// Equator, prime meridian and 100000 m below the WGS 84 spheroid
+const ZERO_ANGLE: u32 = 1 << 31;
+const ZERO_ALTITUDE: u32 = 10_000_000;
+
+/// Decodes size in the "base * 10^exponent centimeters" format
+fn precision_meters(value: u8) -> f64 {
+    // Integer math, as `f64::powi` is not available without `std`
+    let mut centimeters = u64::from(value >> 4);
+    for _ in 0..(value & 0x0F) {
+        centimeters *= 10;
+    }
+    centimeters as f64 / 100.0
+}
+
+impl Record {
+    /// Diameter of the sphere enclosing the entity, in meters
+    pub fn size_meters(&self) -> f64 {
+        precision_meters(self.size)
+    }
+    /// Horizontal precision (diameter of the circle of error), in meters
+    pub fn horizontal_precision_meters(&self) -> f64 {
+        precision_meters(self.horiz_pre)
+    }
+    /// Vertical precision, in meters
+    pub fn vertical_precision_meters(&self) -> f64 {
+        precision_meters(self.vert_pre)
+    }
+    /// Latitude in degrees, positive to the north of the equator
+    pub fn latitude_degrees(&self) -> f64 {
+        (self.latitude as i64 - ZERO_ANGLE as i64) as f64 / 3_600_000.0
+    }
+    /// Longitude in degrees, positive to the east of the prime meridian
+    pub fn longitude_degrees(&self) -> f64 {
+        (self.longitude as i64 - ZERO_ANGLE as i64) as f64 / 3_600_000.0
+    }
+    /// Altitude in meters above the WGS 84 reference spheroid
+    pub fn altitude_meters(&self) -> f64 {
+        (self.altitude as i64 - ZERO_ALTITUDE as i64) as f64 / 100.0
+    }
+}
+
+impl<'a> super::Record<'a> for Record {
+
+    const TYPE: isize = 29;
+
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        if rdata.is_empty() {
+            return Err(Error::WrongRdataLength);
+        }
+        // Format of other versions is unknown, so keep them opaque
+        if rdata[0] != 0 {
+            return Ok(super::RData::Unknown(Type::LOC, rdata));
+        }
+        if rdata.len() != 16 {
+            return Err(Error::WrongRdataLength);
+        }
+        let record = Record {
+            version: rdata[0],
+            size: rdata[1],
+            horiz_pre: rdata[2],
+            vert_pre: rdata[3],
+            latitude: BigEndian::read_u32(&rdata[4..8]),
+            longitude: BigEndian::read_u32(&rdata[8..12]),
+            altitude: BigEndian::read_u32(&rdata[12..16]),
+        };
+        Ok(super::RData::LOC(record))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+    use super::Record;
+
+    #[test]
+    fn parse_response() {
+        // cambridge-net.kei.com LOC 42 21 54 N 71 06 18 W -24m 30m
+        let response = b"\xaa\xbb\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x0dcambridge-net\x03kei\x03com\x00\x00\x1d\x00\x01\
+            \xc0\x0c\x00\x1d\x00\x01\x00\x00\x0e\x10\x00\x10\
+            \x00\x33\x16\x13\x89\x17\x2d\xd0\x70\xbe\x15\xf0\x00\x98\x8d\x20";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::LOC);
+        match packet.answers[0].data {
+            RData::LOC(loc) => {
+                assert_eq!(loc.size_meters(), 30.0);
+                assert_eq!(loc.horizontal_precision_meters(), 10000.0);
+                assert_eq!(loc.vertical_precision_meters(), 10.0);
+                let lat = 42.0 + 21.0/60.0 + 54.0/3600.0;
+                let lon = -(71.0 + 6.0/60.0 + 18.0/3600.0);
+                assert!((loc.latitude_degrees() - lat).abs() < 1e-9);
+                assert!((loc.longitude_degrees() - lon).abs() < 1e-9);
+                assert_eq!(loc.altitude_meters(), -24.0);
+                let loc = Record { size: 0x99, horiz_pre: 0x00,
+                                   vert_pre: 0xFF, ..loc };
+                assert_eq!(loc.size_meters(), 90_000_000.0);
+                assert_eq!(loc.horizontal_precision_meters(), 0.0);
+                assert_eq!(loc.vertical_precision_meters(), 1.5e14);
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+}
@@ -1,11 +1,48 @@
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct Record;
+use core::fmt;
 
-impl<'a> super::Record<'a> for Record {
+use Name;
+
+/// The MG (mail group member) record (EXPERIMENTAL)
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a>(pub Name<'a>);
+
+impl<'a> fmt::Display for Record<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(fmt)
+    }
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
 
     const TYPE: isize = 8;
 
-    fn parse(_rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
-        unimplemented!();
+    fn parse(rdata: &'a [u8], original: &'a [u8]) -> super::RDataResult<'a> {
+        let name = Name::scan(rdata, original)?;
+        let record = Record(name);
+        Ok(super::RData::MG(record))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\xaa\xbb\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x06admins\x07example\x03com\x00\x00\x08\x00\x01\
+            \xc0\x0c\x00\x08\x00\x01\x00\x00\x0e\x10\x00\x08\
+            \x05alice\xc0\x13";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::MG);
+        match packet.answers[0].data {
+            RData::MG(mg) => {
+                assert_eq!(&mg.to_string()[..], "alice.example.com");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
     }
 }
@@ -1,11 +1,52 @@
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct Record;
+use Name;
 
-impl<'a> super::Record<'a> for Record {
+/// The MINFO (mailbox or mail list information) record (EXPERIMENTAL)
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    /// Mailbox responsible for the mailing list or mailbox
+    pub rmailbx: Name<'a>,
+    /// Mailbox to receive error messages
+    pub emailbx: Name<'a>,
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
 
     const TYPE: isize = 14;
 
-    fn parse(_rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
-        unimplemented!();
+    fn parse(rdata: &'a [u8], original: &'a [u8]) -> super::RDataResult<'a> {
+        let rmailbx = Name::scan(rdata, original)?;
+        let emailbx = Name::scan(&rdata[rmailbx.byte_len()..], original)?;
+        let record = Record {
+            rmailbx,
+            emailbx,
+        };
+        Ok(super::RData::MINFO(record))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\xaa\xbb\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x04list\x07example\x03com\x00\x00\x0e\x00\x01\
+            \xc0\x0c\x00\x0e\x00\x01\x00\x00\x0e\x10\x00\x16\
+            \x05owner\xc0\x11\x0blist-errors\xc0\x11";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::MINFO);
+        match packet.answers[0].data {
+            RData::MINFO(minfo) => {
+                assert_eq!(&minfo.rmailbx.to_string()[..],
+                    "owner.example.com");
+                assert_eq!(&minfo.emailbx.to_string()[..],
+                    "list-errors.example.com");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
     }
 }
@@ -6,8 +6,16 @@ pub mod a;
 pub mod aaaa;
 pub mod all;
 pub mod axfr;
+pub mod caa;
+pub mod cdnskey;
+pub mod cds;
 pub mod cname;
+pub mod dname;
+pub mod dnskey;
+pub mod ds;
 pub mod hinfo;
+pub mod https;
+pub mod loc;
 pub mod maila;
 pub mod mailb;
 pub mod mb;
@@ -16,34 +24,76 @@ pub mod mg;
 pub mod minfo;
 pub mod mr;
 pub mod mx;
+pub mod naptr;
 pub mod ns;
 pub mod nsec;
+pub mod nsec3;
+pub mod nsec3param;
 pub mod null;
+pub mod openpgpkey;
 pub mod opt;
 pub mod ptr;
+pub mod rrsig;
+pub mod smimea;
 pub mod soa;
 pub mod srv;
+pub mod sshfp;
+pub mod svcb;
+pub mod tlsa;
 pub mod txt;
+pub mod uri;
 pub mod wks;
 
-use {Type, Error};
+use alloc::vec::Vec;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use {Name, Type, Error, BuildError};
 
 pub use self::a::Record as A;
 pub use self::aaaa::Record as Aaaa;
+pub use self::caa::Record as Caa;
+pub use self::cdnskey::Record as Cdnskey;
+pub use self::cds::Record as Cds;
 pub use self::cname::Record as Cname;
+pub use self::dname::Record as Dname;
+pub use self::dnskey::Record as Dnskey;
+pub use self::ds::Record as Ds;
+pub use self::hinfo::Record as Hinfo;
+pub use self::https::Record as Https;
+pub use self::loc::Record as Loc;
+pub use self::mb::Record as Mb;
+pub use self::mf::Record as Mf;
+pub use self::mg::Record as Mg;
+pub use self::minfo::Record as Minfo;
+pub use self::mr::Record as Mr;
 pub use self::mx::Record as Mx;
+pub use self::naptr::Record as Naptr;
 pub use self::ns::Record as Ns;
 pub use self::nsec::Record as Nsec;
+pub use self::nsec3::Record as Nsec3;
+pub use self::nsec3param::Record as Nsec3param;
+pub use self::null::Record as Null;
+pub use self::openpgpkey::Record as Openpgpkey;
 pub use self::opt::Record as Opt;
+pub use self::opt::EdnsOption;
 pub use self::ptr::Record as Ptr;
+pub use self::rrsig::Record as Rrsig;
+pub use self::smimea::Record as Smimea;
 pub use self::soa::Record as Soa;
 pub use self::srv::Record as Srv;
+pub use self::sshfp::Record as Sshfp;
+pub use self::svcb::Record as Svcb;
+pub use self::svcb::SvcParam;
+pub use self::tlsa::Record as Tlsa;
 pub use self::txt::Record as Txt;
+pub use self::uri::Record as Uri;
+pub use self::wks::Record as Wks;
 
 pub type RDataResult<'a> = Result<RData<'a>, Error>;
 
 /// The enumeration that represents known types of DNS resource records data
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum RData<'a> {
     A(A),
     AAAA(Aaaa),
@@ -54,7 +104,38 @@ pub enum RData<'a> {
     SOA(Soa<'a>),
     SRV(Srv<'a>),
     TXT(Txt<'a>),
+    MF(Mf<'a>),
+    MB(Mb<'a>),
+    MG(Mg<'a>),
+    MR(Mr<'a>),
+    NULL(Null<'a>),
+    WKS(Wks<'a>),
+    HINFO(Hinfo<'a>),
+    MINFO(Minfo<'a>),
+    DS(Ds<'a>),
+    RRSIG(Rrsig<'a>),
+    NSEC(Nsec<'a>),
+    DNSKEY(Dnskey<'a>),
+    NSEC3(Nsec3<'a>),
+    NSEC3PARAM(Nsec3param<'a>),
+    CDS(Cds<'a>),
+    CDNSKEY(Cdnskey<'a>),
+    SSHFP(Sshfp<'a>),
+    TLSA(Tlsa<'a>),
+    SMIMEA(Smimea<'a>),
+    OPENPGPKEY(Openpgpkey<'a>),
+    CAA(Caa<'a>),
+    SVCB(Svcb<'a>),
+    HTTPS(Https<'a>),
+    LOC(Loc),
+    NAPTR(Naptr<'a>),
+    DNAME(Dname<'a>),
+    URI(Uri<'a>),
     /// Anything that can't be parsed yet
+    ///
+    /// Types not known to this library are represented as `Type::Unknown`
+    /// with the numeric code, so any record can be handled as described
+    /// in RFC 3597.
     Unknown(Type, &'a [u8]),
 }
 
@@ -77,13 +158,40 @@ impl<'a> RData<'a> {
             Type::SOA       => Soa::parse(rdata, original),
             Type::SRV       => Srv::parse(rdata, original),
             Type::TXT       => Txt::parse(rdata, original),
+            Type::MF        => Mf::parse(rdata, original),
+            Type::MB        => Mb::parse(rdata, original),
+            Type::MG        => Mg::parse(rdata, original),
+            Type::MR        => Mr::parse(rdata, original),
+            Type::NULL      => Null::parse(rdata, original),
+            Type::WKS       => Wks::parse(rdata, original),
+            Type::HINFO     => Hinfo::parse(rdata, original),
+            Type::MINFO     => Minfo::parse(rdata, original),
+            Type::DS        => Ds::parse(rdata, original),
+            Type::RRSIG     => Rrsig::parse(rdata, original),
+            Type::NSEC      => Nsec::parse(rdata, original),
+            Type::DNSKEY    => Dnskey::parse(rdata, original),
+            Type::NSEC3     => Nsec3::parse(rdata, original),
+            Type::NSEC3PARAM => Nsec3param::parse(rdata, original),
+            Type::CDS       => Cds::parse(rdata, original),
+            Type::CDNSKEY   => Cdnskey::parse(rdata, original),
+            Type::SSHFP     => Sshfp::parse(rdata, original),
+            Type::TLSA      => Tlsa::parse(rdata, original),
+            Type::SMIMEA    => Smimea::parse(rdata, original),
+            Type::OPENPGPKEY => Openpgpkey::parse(rdata, original),
+            Type::CAA       => Caa::parse(rdata, original),
+            Type::SVCB      => Svcb::parse(rdata, original),
+            Type::HTTPS     => Https::parse(rdata, original),
+            Type::LOC       => Loc::parse(rdata, original),
+            Type::NAPTR     => Naptr::parse(rdata, original),
+            Type::DNAME     => Dname::parse(rdata, original),
+            Type::URI       => Uri::parse(rdata, original),
             _               => Ok(RData::Unknown(typ, rdata)),
         }
     }
 
     /// Returns packet type as enum
     ///
-    /// Code can be converted to an integer `packet.type_code() as isize`
+    /// Code can be converted to an integer `u16::from(packet.type_code())`
     pub fn type_code(self) -> Type {
         match self {
             RData::A(..)         => Type::A,
@@ -95,7 +203,222 @@ impl<'a> RData<'a> {
             RData::SOA(..)       => Type::SOA,
             RData::SRV(..)       => Type::SRV,
             RData::TXT(..)       => Type::TXT,
+            RData::MF(..)        => Type::MF,
+            RData::MB(..)        => Type::MB,
+            RData::MG(..)        => Type::MG,
+            RData::MR(..)        => Type::MR,
+            RData::NULL(..)      => Type::NULL,
+            RData::WKS(..)       => Type::WKS,
+            RData::HINFO(..)     => Type::HINFO,
+            RData::MINFO(..)     => Type::MINFO,
+            RData::DS(..)        => Type::DS,
+            RData::RRSIG(..)     => Type::RRSIG,
+            RData::NSEC(..)      => Type::NSEC,
+            RData::DNSKEY(..)    => Type::DNSKEY,
+            RData::NSEC3(..)     => Type::NSEC3,
+            RData::NSEC3PARAM(..) => Type::NSEC3PARAM,
+            RData::CDS(..)       => Type::CDS,
+            RData::CDNSKEY(..)   => Type::CDNSKEY,
+            RData::SSHFP(..)     => Type::SSHFP,
+            RData::TLSA(..)      => Type::TLSA,
+            RData::SMIMEA(..)    => Type::SMIMEA,
+            RData::OPENPGPKEY(..) => Type::OPENPGPKEY,
+            RData::CAA(..)       => Type::CAA,
+            RData::SVCB(..)      => Type::SVCB,
+            RData::HTTPS(..)     => Type::HTTPS,
+            RData::LOC(..)       => Type::LOC,
+            RData::NAPTR(..)     => Type::NAPTR,
+            RData::DNAME(..)     => Type::DNAME,
+            RData::URI(..)       => Type::URI,
             RData::Unknown(t, _) => t,
         }
     }
 }
+
+/// Destination for serialized record data
+pub(crate) trait Writer {
+    fn put_bytes(&mut self, data: &[u8]);
+    /// Writes a domain name
+    ///
+    /// Compression may only be used if `compress` is true, which is
+    /// the case for names in well-known record types (RFC 3597).
+    fn put_name(&mut self, name: &Name, compress: bool);
+
+    fn put_u8(&mut self, value: u8) {
+        self.put_bytes(&[value]);
+    }
+    fn put_u16(&mut self, value: u16) {
+        let mut buf = [0u8; 2];
+        BigEndian::write_u16(&mut buf, value);
+        self.put_bytes(&buf);
+    }
+    fn put_u32(&mut self, value: u32) {
+        let mut buf = [0u8; 4];
+        BigEndian::write_u32(&mut buf, value);
+        self.put_bytes(&buf);
+    }
+    fn put_character_string(&mut self, data: &[u8])
+        -> Result<(), BuildError>
+    {
+        if data.len() > 255 {
+            return Err(BuildError::StringTooLong);
+        }
+        self.put_u8(data.len() as u8);
+        self.put_bytes(data);
+        Ok(())
+    }
+}
+
+impl Writer for Vec<u8> {
+    fn put_bytes(&mut self, data: &[u8]) {
+        self.extend_from_slice(data);
+    }
+    fn put_name(&mut self, name: &Name, _compress: bool) {
+        name.write_uncompressed(self);
+    }
+}
+
+impl<'a> RData<'a> {
+    /// Serializes record data, names are written in the same form as
+    /// `Name::scan` accepts them, so the output can be parsed back
+    ///
+    /// Fails if a character string in a public field is longer than
+    /// 255 bytes.
+    pub(crate) fn write<W: Writer>(&self, w: &mut W)
+        -> Result<(), BuildError>
+    {
+        match *self {
+            RData::A(ref a) => w.put_bytes(&a.0.octets()),
+            RData::AAAA(ref aaaa) => w.put_bytes(&aaaa.0.octets()),
+            RData::CNAME(ref cname) => w.put_name(&cname.0, true),
+            RData::NS(ref ns) => w.put_name(&ns.0, true),
+            RData::PTR(ref ptr) => w.put_name(&ptr.0, true),
+            RData::MF(ref mf) => w.put_name(&mf.0, true),
+            RData::MB(ref mb) => w.put_name(&mb.0, true),
+            RData::MG(ref mg) => w.put_name(&mg.0, true),
+            RData::MR(ref mr) => w.put_name(&mr.0, true),
+            RData::DNAME(ref dname) => w.put_name(&dname.0, false),
+            RData::MX(ref mx) => {
+                w.put_u16(mx.preference);
+                w.put_name(&mx.exchange, true);
+            }
+            RData::SOA(ref soa) => {
+                w.put_name(&soa.primary_ns, true);
+                w.put_name(&soa.mailbox, true);
+                w.put_u32(soa.serial);
+                w.put_u32(soa.refresh);
+                w.put_u32(soa.retry);
+                w.put_u32(soa.expire);
+                w.put_u32(soa.minimum_ttl);
+            }
+            RData::SRV(ref srv) => {
+                w.put_u16(srv.priority);
+                w.put_u16(srv.weight);
+                w.put_u16(srv.port);
+                w.put_name(&srv.target, false);
+            }
+            RData::TXT(ref txt) => w.put_bytes(txt.bytes),
+            RData::NULL(ref null) => w.put_bytes(null.0),
+            RData::WKS(ref wks) => {
+                w.put_bytes(&wks.address.octets());
+                w.put_u8(wks.protocol);
+                w.put_bytes(wks.bitmap);
+            }
+            RData::HINFO(ref hinfo) => {
+                w.put_character_string(hinfo.cpu)?;
+                w.put_character_string(hinfo.os)?;
+            }
+            RData::MINFO(ref minfo) => {
+                w.put_name(&minfo.rmailbx, true);
+                w.put_name(&minfo.emailbx, true);
+            }
+            RData::DS(ref ds) | RData::CDS(Cds(ref ds)) => {
+                w.put_u16(ds.key_tag);
+                w.put_u8(ds.algorithm);
+                w.put_u8(ds.digest_type);
+                w.put_bytes(ds.digest);
+            }
+            RData::DNSKEY(ref key) | RData::CDNSKEY(Cdnskey(ref key)) => {
+                w.put_u16(key.flags);
+                w.put_u8(key.protocol);
+                w.put_u8(key.algorithm);
+                w.put_bytes(key.public_key);
+            }
+            RData::RRSIG(ref sig) => {
+                w.put_u16(sig.type_covered.into());
+                w.put_u8(sig.algorithm);
+                w.put_u8(sig.labels);
+                w.put_u32(sig.original_ttl);
+                w.put_u32(sig.expiration);
+                w.put_u32(sig.inception);
+                w.put_u16(sig.key_tag);
+                w.put_name(&sig.signer_name, false);
+                w.put_bytes(sig.signature);
+            }
+            RData::NSEC(ref nsec) => {
+                w.put_name(&nsec.next_domain, false);
+                w.put_bytes(nsec.types.bytes);
+            }
+            RData::NSEC3(ref nsec3) => {
+                w.put_u8(nsec3.hash_algorithm);
+                w.put_u8(nsec3.flags);
+                w.put_u16(nsec3.iterations);
+                w.put_character_string(nsec3.salt)?;
+                w.put_character_string(nsec3.next_hashed_owner)?;
+                w.put_bytes(nsec3.types.bytes);
+            }
+            RData::NSEC3PARAM(ref param) => {
+                w.put_u8(param.hash_algorithm);
+                w.put_u8(param.flags);
+                w.put_u16(param.iterations);
+                w.put_character_string(param.salt)?;
+            }
+            RData::SSHFP(ref sshfp) => {
+                w.put_u8(sshfp.algorithm.into());
+                w.put_u8(sshfp.fingerprint_type.into());
+                w.put_bytes(sshfp.fingerprint);
+            }
+            RData::TLSA(ref tlsa) | RData::SMIMEA(Smimea(ref tlsa)) => {
+                w.put_u8(tlsa.cert_usage.into());
+                w.put_u8(tlsa.selector.into());
+                w.put_u8(tlsa.matching_type.into());
+                w.put_bytes(tlsa.data);
+            }
+            RData::OPENPGPKEY(ref key) => w.put_bytes(key.0),
+            RData::CAA(ref caa) => {
+                w.put_u8(caa.flags);
+                w.put_character_string(caa.tag.as_bytes())?;
+                w.put_bytes(caa.value);
+            }
+            RData::SVCB(ref svcb) | RData::HTTPS(Https(ref svcb)) => {
+                w.put_u16(svcb.priority);
+                w.put_name(&svcb.target, false);
+                w.put_bytes(svcb.params);
+            }
+            RData::LOC(ref loc) => {
+                w.put_u8(loc.version);
+                w.put_u8(loc.size);
+                w.put_u8(loc.horiz_pre);
+                w.put_u8(loc.vert_pre);
+                w.put_u32(loc.latitude);
+                w.put_u32(loc.longitude);
+                w.put_u32(loc.altitude);
+            }
+            RData::NAPTR(ref naptr) => {
+                w.put_u16(naptr.order);
+                w.put_u16(naptr.preference);
+                w.put_character_string(naptr.flags)?;
+                w.put_character_string(naptr.services)?;
+                w.put_character_string(naptr.regexp)?;
+                w.put_name(&naptr.replacement, false);
+            }
+            RData::URI(ref uri) => {
+                w.put_u16(uri.priority);
+                w.put_u16(uri.weight);
+                w.put_bytes(uri.target);
+            }
+            RData::Unknown(_, data) => w.put_bytes(data),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use {Name, Error};
+use byteorder::{BigEndian, ByteOrder};
+
+/// The NAPTR (Naming Authority Pointer) record (RFC 3403)
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub order: u16,
+    pub preference: u16,
+    /// Flags controlling rewriting, e.g. `U`, `S`, `A` or `P`
+    pub flags: &'a [u8],
+    /// Service parameters, e.g. `E2U+sip`
+    pub services: &'a [u8],
+    /// Substitution expression applied to the original string
+    pub regexp: &'a [u8],
+    /// Next domain name to query, root if `regexp` is used
+    pub replacement: Name<'a>,
+}
+
+fn character_string<'a>(rdata: &'a [u8], pos: &mut usize)
+    -> Result<&'a [u8], Error>
+{
+    if rdata.len() <= *pos {
+        return Err(Error::WrongRdataLength);
+    }
+    let start = *pos + 1;
+    let end = start + rdata[*pos] as usize;
+    if rdata.len() < end {
+        return Err(Error::WrongRdataLength);
+    }
+    *pos = end;
+    Ok(&rdata[start..end])
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 35;
+
+    fn parse(rdata: &'a [u8], original: &'a [u8]) -> super::RDataResult<'a> {
+        if rdata.len() < 4 {
+            return Err(Error::WrongRdataLength);
+        }
+        let mut pos = 4;
+        let flags = character_string(rdata, &mut pos)?;
+        let services = character_string(rdata, &mut pos)?;
+        let regexp = character_string(rdata, &mut pos)?;
+        let record = Record {
+            order: BigEndian::read_u16(&rdata[..2]),
+            preference: BigEndian::read_u16(&rdata[2..4]),
+            flags,
+            services,
+            regexp,
+            replacement: Name::scan(&rdata[pos..], original)?,
+        };
+        Ok(super::RData::NAPTR(record))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\xaa\xbb\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x015\x015\x015\x012\x015\x015\x015\x012\x012\x014\x011\
+            \x04e164\x04arpa\x00\x00\x23\x00\x01\
+            \xc0\x0c\x00\x23\x00\x01\x00\x00\x0e\x10\x00\x2b\
+            \x00\x64\x00\x0a\x01u\x07E2U+sip\
+            \x1b!^.*$!sip:info@example.com!\x00";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::NAPTR);
+        match packet.answers[0].data {
+            RData::NAPTR(naptr) => {
+                assert_eq!(naptr.order, 100);
+                assert_eq!(naptr.preference, 10);
+                assert_eq!(naptr.flags, b"u");
+                assert_eq!(naptr.services, b"E2U+sip");
+                assert_eq!(naptr.regexp, &b"!^.*$!sip:info@example.com!"[..]);
+                assert_eq!(&naptr.replacement.to_string()[..], "");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+}
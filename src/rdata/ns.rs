@@ -1,3 +1,5 @@
+use alloc::string::{String, ToString};
+
 use Name;
 
 #[derive(Debug, Clone, Copy)]
@@ -1,11 +1,153 @@
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct Record;
+use {Name, Type, Error};
 
-impl<'a> super::Record<'a> for Record {
+/// The NSEC (Next Secure) record (RFC 4034)
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub next_domain: Name<'a>,
+    pub types: TypeBitmap<'a>,
+}
+
+/// Set of types in the NSEC and NSEC3 records (RFC 4034 Section 4.1.2)
+#[derive(Debug, Clone, Copy)]
+pub struct TypeBitmap<'a> {
+    pub(crate) bytes: &'a [u8],
+}
+
+/// Iterator over types in the `TypeBitmap`, in increasing order
+#[derive(Debug, Clone)]
+pub struct TypeBitmapIter<'a> {
+    bytes: &'a [u8],
+    /// Bit index in the current window, i.e. in `bytes[2..]`
+    bit: usize,
+}
+
+impl<'a> TypeBitmap<'a> {
+    /// Checks the format of the bitmap and wraps it
+    pub fn parse(bytes: &'a [u8]) -> Result<TypeBitmap<'a>, Error> {
+        let mut pos = 0;
+        let mut prev_window = None;
+        while pos < bytes.len() {
+            if bytes.len() < pos + 2 {
+                return Err(Error::WrongRdataLength);
+            }
+            let window = bytes[pos];
+            let len = bytes[pos+1] as usize;
+            if len == 0 || len > 32 || prev_window >= Some(window) {
+                return Err(Error::WrongRdataLength);
+            }
+            pos += 2 + len;
+            if bytes.len() < pos {
+                return Err(Error::WrongRdataLength);
+            }
+            prev_window = Some(window);
+        }
+        Ok(TypeBitmap { bytes })
+    }
+    /// Returns iterator over the types
+    pub fn iter(&self) -> TypeBitmapIter<'a> {
+        TypeBitmapIter {
+            bytes: self.bytes,
+            bit: 0,
+        }
+    }
+    /// Returns true if type is in the set
+    pub fn contains(&self, typ: Type) -> bool {
+        let code = u16::from(typ);
+        let window = (code >> 8) as u8;
+        let index = (code & 0xFF) as usize;
+        let mut pos = 0;
+        while pos < self.bytes.len() {
+            let len = self.bytes[pos+1] as usize;
+            if self.bytes[pos] == window {
+                return index / 8 < len &&
+                    self.bytes[pos+2+index/8] & (0x80 >> (index % 8)) != 0;
+            }
+            pos += 2 + len;
+        }
+        false
+    }
+    /// Returns true if there are no types in the set
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+}
+
+impl<'a> Iterator for TypeBitmapIter<'a> {
+    type Item = Type;
+    fn next(&mut self) -> Option<Type> {
+        while self.bytes.len() >= 2 {
+            let len = self.bytes[1] as usize;
+            while self.bit < len*8 {
+                let bit = self.bit;
+                self.bit += 1;
+                if self.bytes[2 + bit/8] & (0x80 >> (bit % 8)) != 0 {
+                    let code = ((self.bytes[0] as u16) << 8) | bit as u16;
+                    return Some(code.into());
+                }
+            }
+            self.bytes = &self.bytes[2+len..];
+            self.bit = 0;
+        }
+        None
+    }
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
 
     const TYPE: isize = 47;
 
-    fn parse(_rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
-        unimplemented!();
+    fn parse(rdata: &'a [u8], original: &'a [u8]) -> super::RDataResult<'a> {
+        let next_domain = Name::scan(rdata, original)?;
+        let types = TypeBitmap::parse(&rdata[next_domain.byte_len()..])?;
+        Ok(super::RData::NSEC(Record { next_domain, types }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use {Packet, Type};
+    use QueryType as QT;
+    use RData;
+    use super::*;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\x124\x85\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x04alfa\x07example\x03com\x00\x00/\x00\x01\
+            \xc0\x0c\x00/\x00\x01\x00\x01Q\x80\x007\
+            \x04host\x07example\x03com\x00\x00\x06@\x01\x00\x00\x00\x03\x04\
+            \x1b\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+            \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x20";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::NSEC);
+        match packet.answers[0].data {
+            RData::NSEC(nsec) => {
+                assert_eq!(&nsec.next_domain.to_string()[..],
+                    "host.example.com");
+                assert_eq!(nsec.types.iter().collect::<Vec<_>>(), vec![
+                    Type::A, Type::MX, Type::RRSIG, Type::NSEC,
+                    Type::Unknown(1234),
+                ]);
+                assert!(nsec.types.contains(Type::MX));
+                assert!(nsec.types.contains(Type::Unknown(1234)));
+                assert!(!nsec.types.contains(Type::AAAA));
+                assert!(!nsec.types.contains(Type::Unknown(1235)));
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+
+    #[test]
+    fn invalid_bitmaps() {
+        // empty window
+        assert!(TypeBitmap::parse(b"\x00\x00").is_err());
+        // too long window
+        assert!(TypeBitmap::parse(&[0u8, 33][..]).is_err());
+        // windows out of order
+        assert!(TypeBitmap::parse(b"\x01\x01\x40\x00\x01\x40").is_err());
+        // truncated
+        assert!(TypeBitmap::parse(b"\x00\x02\x40").is_err());
+        assert!(TypeBitmap::parse(b"").unwrap().is_empty());
     }
 }
@@ -0,0 +1,92 @@
+use Error;
+use byteorder::{BigEndian, ByteOrder};
+use super::nsec::TypeBitmap;
+
+/// The NSEC3 record (RFC 5155)
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub hash_algorithm: u8,
+    pub flags: u8,
+    pub iterations: u16,
+    pub salt: &'a [u8],
+    /// Binary (not base32 encoded) hash of the next owner name
+    pub next_hashed_owner: &'a [u8],
+    pub types: TypeBitmap<'a>,
+}
+
+const OPT_OUT: u8 = 0x01;
+
+impl<'a> Record<'a> {
+    /// The opt-out flag, i.e. insecure delegations may be not covered
+    pub fn is_opt_out(&self) -> bool {
+        self.flags & OPT_OUT != 0
+    }
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 50;
+
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        if rdata.len() < 5 {
+            return Err(Error::WrongRdataLength);
+        }
+        let salt_end = 5 + rdata[4] as usize;
+        if rdata.len() < salt_end + 1 {
+            return Err(Error::WrongRdataLength);
+        }
+        let hash_end = salt_end + 1 + rdata[salt_end] as usize;
+        if rdata.len() < hash_end {
+            return Err(Error::WrongRdataLength);
+        }
+        let record = Record {
+            hash_algorithm: rdata[0],
+            flags: rdata[1],
+            iterations: BigEndian::read_u16(&rdata[2..4]),
+            salt: &rdata[5..salt_end],
+            next_hashed_owner: &rdata[salt_end+1..hash_end],
+            types: TypeBitmap::parse(&rdata[hash_end..])?,
+        };
+        Ok(super::RData::NSEC3(record))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use {Packet, Type};
+    use ResponseCode::NameError;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\x124\x85\x83\x00\x01\x00\x00\x00\x01\x00\x00\
+            \x01a\x01c\x01x\x01w\x07example\x00\x00\x01\x00\x01\
+            \x200p9mhaveqvm6t7vbl5lop2u3t2rp3tom\x07example\x00\
+            \x002\x00\x01\x00\x00\x0e\x10\x00'\x01\x01\
+            \x00\x0c\x04\xaa\xbb\xcc\xdd\x14\x17N\xb2@\x9f\xe2\x8b\xcbH\x87\
+            \xa1\x83o\x95\x7f\x0a\x84%\xe2{\x00\x07\"\x01\x00\x00\x00\x02\
+            \x90";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.header.response_code, NameError);
+        assert_eq!(&packet.nameservers[0].name.to_string()[..],
+            "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.example");
+        match packet.nameservers[0].data {
+            RData::NSEC3(nsec3) => {
+                assert_eq!(nsec3.hash_algorithm, 1);
+                assert!(nsec3.is_opt_out());
+                assert_eq!(nsec3.iterations, 12);
+                assert_eq!(nsec3.salt, b"\xaa\xbb\xcc\xdd");
+                // 2t7b4g4vsa5smi47k61mv5bv1a22bojr in base32hex
+                assert_eq!(nsec3.next_hashed_owner,
+                    &b"\x17N\xb2@\x9f\xe2\x8b\xcbH\x87\
+                       \xa1\x83o\x95\x7f\x0a\x84%\xe2{"[..]);
+                assert_eq!(nsec3.types.iter().collect::<Vec<_>>(), vec![
+                    Type::NS, Type::SOA, Type::MX, Type::RRSIG,
+                    Type::DNSKEY, Type::NSEC3PARAM,
+                ]);
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+}
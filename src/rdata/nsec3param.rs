@@ -0,0 +1,56 @@
+use Error;
+use byteorder::{BigEndian, ByteOrder};
+
+/// The NSEC3PARAM record (RFC 5155)
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub hash_algorithm: u8,
+    pub flags: u8,
+    pub iterations: u16,
+    pub salt: &'a [u8],
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 51;
+
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        if rdata.len() < 5 || rdata.len() != 5 + rdata[4] as usize {
+            return Err(Error::WrongRdataLength);
+        }
+        let record = Record {
+            hash_algorithm: rdata[0],
+            flags: rdata[1],
+            iterations: BigEndian::read_u16(&rdata[2..4]),
+            salt: &rdata[5..],
+        };
+        Ok(super::RData::NSEC3PARAM(record))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\x124\x85\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x07example\x00\x003\x00\x01\
+            \xc0\x0c\x003\x00\x01\x00\x00\x0e\x10\x00\x09\x01\x00\
+            \x00\x0c\x04\xaa\xbb\xcc\xdd";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::NSEC3PARAM);
+        match packet.answers[0].data {
+            RData::NSEC3PARAM(param) => {
+                assert_eq!(param.hash_algorithm, 1);
+                assert_eq!(param.flags, 0);
+                assert_eq!(param.iterations, 12);
+                assert_eq!(param.salt, b"\xaa\xbb\xcc\xdd");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+}
@@ -1,11 +1,12 @@
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct Record;
+/// The NULL record (EXPERIMENTAL), arbitrary data up to 65535 bytes
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a>(pub &'a [u8]);
 
-impl<'a> super::Record<'a> for Record {
+impl<'a> super::Record<'a> for Record<'a> {
 
     const TYPE: isize = 10;
 
-    fn parse(_rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
-        unimplemented!();
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        Ok(super::RData::NULL(Record(rdata)))
     }
 }
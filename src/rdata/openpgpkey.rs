@@ -0,0 +1,39 @@
+/// The OPENPGPKEY record (RFC 7929)
+///
+/// Contains a single OpenPGP Transferable Public Key in binary form
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a>(pub &'a [u8]);
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 61;
+
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        Ok(super::RData::OPENPGPKEY(Record(rdata)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\xaa\xbb\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x38c93f1e400f26708f98cb19d936620da35eec8f72e57f9eec01c1afd6\
+            \x0b_openpgpkey\x07example\x03com\x00\x00=\x00\x01\
+            \xc0\x0c\x00=\x00\x01\x00\x00\x0e\x10\x00\x06\
+            \x99\x01\x0d\x04R\x81";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::OPENPGPKEY);
+        match packet.answers[0].data {
+            RData::OPENPGPKEY(key) => {
+                assert_eq!(key.0, b"\x99\x01\x0d\x04R\x81");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+}
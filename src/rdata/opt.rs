@@ -1,18 +1,342 @@
+use net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use core::str::from_utf8;
+
+use {Name, Error};
+use byteorder::{BigEndian, ByteOrder};
+
 /// RFC 6891 OPT RR
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Record<'a> {
     pub udp: u16,
     pub extrcode: u8,
     pub version: u8,
     pub flags: u16,
-    pub data: super::RData<'a>,
+    /// Raw option data, use `options()` to iterate over decoded options
+    pub data: &'a [u8],
+}
+
+/// A single EDNS option
+#[derive(Debug, Clone, Copy)]
+pub enum EdnsOption<'a> {
+    /// Name Server Identifier (RFC 5001)
+    Nsid(&'a [u8]),
+    /// Client Subnet (RFC 7871)
+    ClientSubnet(ClientSubnet),
+    /// EDNS Expire (RFC 7314), no value in queries
+    Expire(Option<u32>),
+    /// DNS Cookie (RFC 7873), server cookie is absent in initial query
+    Cookie {
+        client: &'a [u8],
+        server: Option<&'a [u8]>,
+    },
+    /// TCP Keepalive (RFC 7828) in units of 100 milliseconds,
+    /// no value in queries
+    TcpKeepalive(Option<u16>),
+    /// Padding (RFC 7830)
+    Padding(&'a [u8]),
+    /// CHAIN query requests (RFC 7901), the closest trust point
+    Chain(Name<'a>),
+    /// Extended DNS Error (RFC 8914)
+    ExtendedError {
+        info_code: u16,
+        extra_text: &'a str,
+    },
+    /// Any option not listed above, with its code and raw data
+    Unknown(u16, &'a [u8]),
+}
+
+/// Client Subnet option (RFC 7871)
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ClientSubnet {
+    pub source_prefix_len: u8,
+    pub scope_prefix_len: u8,
+    /// Address with all bits beyond source prefix set to zero
+    pub address: IpAddr,
+}
+
+#[derive(Debug, Clone)]
+pub struct OptionIter<'a> {
+    bytes: &'a [u8],
+}
+
+pub const NSID: u16 = 3;
+pub const CLIENT_SUBNET: u16 = 8;
+pub const EXPIRE: u16 = 9;
+pub const COOKIE: u16 = 10;
+pub const TCP_KEEPALIVE: u16 = 11;
+pub const PADDING: u16 = 12;
+pub const CHAIN: u16 = 13;
+pub const EXTENDED_ERROR: u16 = 15;
+
+impl<'a> Iterator for OptionIter<'a> {
+    type Item = Result<EdnsOption<'a>, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        if self.bytes.len() < 4 {
+            self.bytes = &[];
+            return Some(Err(Error::WrongRdataLength));
+        }
+        let code = BigEndian::read_u16(&self.bytes[..2]);
+        let len = BigEndian::read_u16(&self.bytes[2..4]) as usize;
+        if self.bytes.len() < len+4 {
+            self.bytes = &[];
+            return Some(Err(Error::WrongRdataLength));
+        }
+        let (head, tail) = self.bytes[4..].split_at(len);
+        self.bytes = tail;
+        Some(EdnsOption::parse(code, head))
+    }
+}
+
+impl<'a> Record<'a> {
+    // Not a `super::Record`, as the fixed fields are stored in class and
+    // TTL of the pseudo-RR, so it can't be decoded from record data alone
+    pub(crate) const TYPE: isize = 41;
+
+    /// Checks that option data consists of well-formed TLVs
+    ///
+    /// The content of each option is only checked when iterating.
+    pub fn check_options(data: &[u8]) -> Result<(), Error> {
+        let mut pos = 0;
+        while pos < data.len() {
+            if data.len() < pos + 4 {
+                return Err(Error::WrongRdataLength);
+            }
+            let len = BigEndian::read_u16(&data[pos+2..pos+4]) as usize;
+            pos += 4 + len;
+            if data.len() < pos {
+                return Err(Error::WrongRdataLength);
+            }
+        }
+        Ok(())
+    }
+    /// Returns iterator over EDNS options
+    pub fn options(&self) -> OptionIter<'a> {
+        OptionIter {
+            bytes: self.data,
+        }
+    }
+    /// Returns true if DNSSEC OK flag is set (RFC 3225)
+    pub fn dnssec_ok(&self) -> bool {
+        self.flags & 0x8000 != 0
+    }
+}
+
+impl<'a> EdnsOption<'a> {
+    /// Parse option data of the option with specified code
+    pub fn parse(code: u16, data: &'a [u8]) -> Result<EdnsOption<'a>, Error> {
+        match code {
+            NSID => Ok(EdnsOption::Nsid(data)),
+            CLIENT_SUBNET => {
+                ClientSubnet::parse(data).map(EdnsOption::ClientSubnet)
+            }
+            EXPIRE => match data.len() {
+                0 => Ok(EdnsOption::Expire(None)),
+                4 => Ok(EdnsOption::Expire(Some(BigEndian::read_u32(data)))),
+                _ => Err(Error::BadEdnsOption(code)),
+            },
+            COOKIE => match data.len() {
+                8 => Ok(EdnsOption::Cookie { client: data, server: None }),
+                16..=40 => Ok(EdnsOption::Cookie {
+                    client: &data[..8],
+                    server: Some(&data[8..]),
+                }),
+                _ => Err(Error::BadEdnsOption(code)),
+            },
+            TCP_KEEPALIVE => match data.len() {
+                0 => Ok(EdnsOption::TcpKeepalive(None)),
+                2 => Ok(EdnsOption::TcpKeepalive(
+                    Some(BigEndian::read_u16(data)))),
+                _ => Err(Error::BadEdnsOption(code)),
+            },
+            PADDING => Ok(EdnsOption::Padding(data)),
+            CHAIN => {
+                // Empty original buffer makes any compression pointer
+                // invalid, names in options must not be compressed
+                let name = Name::scan(data, &data[..0])
+                    .map_err(|_| Error::BadEdnsOption(code))?;
+                if name.byte_len() != data.len() {
+                    return Err(Error::BadEdnsOption(code));
+                }
+                Ok(EdnsOption::Chain(name))
+            }
+            EXTENDED_ERROR => {
+                if data.len() < 2 {
+                    return Err(Error::BadEdnsOption(code));
+                }
+                Ok(EdnsOption::ExtendedError {
+                    info_code: BigEndian::read_u16(&data[..2]),
+                    extra_text: from_utf8(&data[2..])
+                        .map_err(|_| Error::BadEdnsOption(code))?,
+                })
+            }
+            _ => Ok(EdnsOption::Unknown(code, data)),
+        }
+    }
+    /// Returns the option code
+    pub fn code(&self) -> u16 {
+        match *self {
+            EdnsOption::Nsid(..)               => NSID,
+            EdnsOption::ClientSubnet(..)       => CLIENT_SUBNET,
+            EdnsOption::Expire(..)             => EXPIRE,
+            EdnsOption::Cookie { .. }          => COOKIE,
+            EdnsOption::TcpKeepalive(..)       => TCP_KEEPALIVE,
+            EdnsOption::Padding(..)            => PADDING,
+            EdnsOption::Chain(..)              => CHAIN,
+            EdnsOption::ExtendedError { .. }   => EXTENDED_ERROR,
+            EdnsOption::Unknown(code, _)       => code,
+        }
+    }
+}
+
+impl ClientSubnet {
+    fn parse(data: &[u8]) -> Result<ClientSubnet, Error> {
+        let err = Error::BadEdnsOption(CLIENT_SUBNET);
+        if data.len() < 4 {
+            return Err(err);
+        }
+        let family = BigEndian::read_u16(&data[..2]);
+        let source_prefix_len = data[2];
+        let scope_prefix_len = data[3];
+        let addr = &data[4..];
+        if addr.len() != (source_prefix_len as usize + 7) / 8 {
+            return Err(err);
+        }
+        let bits = source_prefix_len % 8;
+        if bits > 0 && addr[addr.len()-1] & (0xFF >> bits) != 0 {
+            return Err(err);
+        }
+        let address = match family {
+            1 if source_prefix_len <= 32 => {
+                let mut octets = [0u8; 4];
+                octets[..addr.len()].copy_from_slice(addr);
+                IpAddr::V4(Ipv4Addr::from(octets))
+            }
+            2 if source_prefix_len <= 128 => {
+                let mut octets = [0u8; 16];
+                octets[..addr.len()].copy_from_slice(addr);
+                IpAddr::V6(Ipv6Addr::from(octets))
+            }
+            _ => return Err(err),
+        };
+        Ok(ClientSubnet {
+            source_prefix_len,
+            scope_prefix_len,
+            address,
+        })
+    }
 }
 
-impl<'a> super::Record<'a> for Record<'a> {
+#[cfg(test)]
+mod test {
+
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use Packet;
+    use QueryType as QT;
+    use super::*;
+
+    #[test]
+    fn parse_query_with_options() {
+        let query = b"\x95\xce\x01\x00\x00\x01\x00\x00\x00\x00\x00\x01\
+            \x06google\x03com\x00\x00\x01\x00\x01\
+            \x00\x00\x29\x10\x00\x00\x00\x80\x00\x00\x25\
+            \x00\x08\x00\x07\x00\x01\x18\x00\xc0\x00\x02\
+            \x00\x0a\x00\x08\x01\x02\x03\x04\x05\x06\x07\x08\
+            \x00\x03\x00\x00\
+            \x00\x0b\x00\x00\
+            \x00\xff\x00\x02\xab\xcd";
+        let packet = Packet::parse(query).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::A);
+        let opt = packet.opt.unwrap();
+        assert_eq!(opt.udp, 4096);
+        assert!(opt.dnssec_ok());
+        let options = opt.options()
+            .collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(options.len(), 5);
+        match options[0] {
+            EdnsOption::ClientSubnet(ecs) => {
+                assert_eq!(ecs, ClientSubnet {
+                    source_prefix_len: 24,
+                    scope_prefix_len: 0,
+                    address: IpAddr::V4(Ipv4Addr::new(192, 0, 2, 0)),
+                });
+            }
+            ref x => panic!("Wrong option {:?}", x),
+        }
+        match options[1] {
+            EdnsOption::Cookie { client, server } => {
+                assert_eq!(client, b"\x01\x02\x03\x04\x05\x06\x07\x08");
+                assert_eq!(server, None);
+            }
+            ref x => panic!("Wrong option {:?}", x),
+        }
+        assert_matches!(options[2], EdnsOption::Nsid(b""));
+        assert_matches!(options[3], EdnsOption::TcpKeepalive(None));
+        assert_matches!(options[4], EdnsOption::Unknown(255, b"\xab\xcd"));
+    }
+
+    #[test]
+    fn parse_response_options() {
+        let data = b"\x00\x0f\x00\x09\x00\x12signed\x00\
+            \x00\x0d\x00\x0d\x07example\x03com\x00\
+            \x00\x09\x00\x04\x00\x00\x0e\x10";
+        Record::check_options(data).unwrap();
+        let opt = Record { udp: 1232, extrcode: 0, version: 0, flags: 0,
+                           data };
+        let mut options = opt.options();
+        match options.next() {
+            Some(Ok(EdnsOption::ExtendedError { info_code, extra_text })) => {
+                assert_eq!(info_code, 18);
+                assert_eq!(extra_text, "signed\0");
+            }
+            x => panic!("Wrong option {:?}", x),
+        }
+        match options.next() {
+            Some(Ok(EdnsOption::Chain(name))) => {
+                assert_eq!(&name.to_string()[..], "example.com");
+            }
+            x => panic!("Wrong option {:?}", x),
+        }
+        assert_matches!(options.next(), Some(Ok(EdnsOption::Expire(Some(3600)))));
+        assert!(options.next().is_none());
+    }
 
-    const TYPE: isize = 41;
+    #[test]
+    fn malformed_options() {
+        assert_matches!(Record::check_options(b"\x00\x0a\x00\x08\x01"),
+                        Err(Error::WrongRdataLength));
+        assert_matches!(Record::check_options(b"\x00\x0a\x00"),
+                        Err(Error::WrongRdataLength));
+        assert_matches!(EdnsOption::parse(COOKIE, b"\x01\x02"),
+                        Err(Error::BadEdnsOption(COOKIE)));
+        assert_matches!(EdnsOption::parse(CLIENT_SUBNET, b"\x00\x01\x18\x00\xc0"),
+                        Err(Error::BadEdnsOption(CLIENT_SUBNET)));
+        // bits beyond the source prefix must be zero
+        assert_matches!(EdnsOption::parse(CLIENT_SUBNET,
+                                          b"\x00\x01\x17\x00\xc0\x00\x03"),
+                        Err(Error::BadEdnsOption(CLIENT_SUBNET)));
+        assert_matches!(EdnsOption::parse(CLIENT_SUBNET,
+                                          b"\x00\x01\x17\x00\xc0\x00\x02"),
+                        Ok(EdnsOption::ClientSubnet(..)));
+        // compression pointers are not allowed in option data
+        assert_matches!(EdnsOption::parse(CHAIN, b"\x01a\xc0\x00"),
+                        Err(Error::BadEdnsOption(CHAIN)));
+    }
 
-    fn parse(_rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
-        unimplemented!();
+    #[test]
+    fn truncated_options() {
+        let opt = Record { udp: 512, extrcode: 0, version: 0, flags: 0,
+                           data: b"\x00\x03\x00\x10ab\x00\x03\x00\x00" };
+        let mut options = opt.options();
+        assert_matches!(options.next(), Some(Err(Error::WrongRdataLength)));
+        assert!(options.next().is_none());
+        let opt = Record { data: b"\x00\x03\x00\x00\x00", ..opt };
+        let mut options = opt.options();
+        assert_matches!(options.next(), Some(Ok(EdnsOption::Nsid(b""))));
+        assert_matches!(options.next(), Some(Err(Error::WrongRdataLength)));
+        assert!(options.next().is_none());
     }
 }
@@ -0,0 +1,94 @@
+use {Name, Type, Error};
+use byteorder::{BigEndian, ByteOrder};
+
+/// The RRSIG record (RFC 4034)
+///
+/// Times are in seconds since the unix epoch, modulo 2**32 as defined
+/// by RFC 4034 Section 3.1.5.
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub type_covered: Type,
+    pub algorithm: u8,
+    pub labels: u8,
+    pub original_ttl: u32,
+    pub expiration: u32,
+    pub inception: u32,
+    pub key_tag: u16,
+    pub signer_name: Name<'a>,
+    pub signature: &'a [u8],
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 46;
+
+    fn parse(rdata: &'a [u8], original: &'a [u8]) -> super::RDataResult<'a> {
+        if rdata.len() < 19 {
+            return Err(Error::WrongRdataLength);
+        }
+        let signer_name = Name::scan(&rdata[18..], original)?;
+        let pos = 18 + signer_name.byte_len();
+        let record = Record {
+            type_covered: BigEndian::read_u16(&rdata[..2]).into(),
+            algorithm: rdata[2],
+            labels: rdata[3],
+            original_ttl: BigEndian::read_u32(&rdata[4..8]),
+            expiration: BigEndian::read_u32(&rdata[8..12]),
+            inception: BigEndian::read_u32(&rdata[12..16]),
+            key_tag: BigEndian::read_u16(&rdata[16..18]),
+            signer_name,
+            signature: &rdata[pos..],
+        };
+        Ok(super::RData::RRSIG(record))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::net::Ipv4Addr;
+
+    use {Packet, Type};
+    use QueryType as QT;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\x124\x85\x80\x00\x01\x00\x02\x00\x00\x00\x00\
+            \x04host\x07example\x03com\x00\x00\x01\x00\x01\
+            \xc0\x0c\x00\x01\x00\x01\x00\x01Q\x80\x00\x04\xc0\x00\x02\x01\
+            \xc0\x0c\x00.\x00\x01\x00\x01Q\x80\x00\
+            \x9f\x00\x01\x05\x03\x00\x01Q\x80>|\x9d\xd7>U\x10\xd7\x0aR\x07ex\
+            ample\x03com\x00\xa0\x90u[\xa5\x8d\x1a\xff\xa5v\xf47X1\xb41\x09\
+            \x20\xe4\x81!\x8d\x18\xa9\xf1d\xeb=\x81\xaf\xd3\xb8u\xd3\xc7T(c\
+            \x1e\x0c\xf2\xa2\x8dP\x87_p\xc3)\xd7\xdb\xfa\xfe\xa8\x07\xdc\x1f\
+            \xba\x1d\xc3L\x95\xd4\x01\xf2?3L\xe6;\xfc\xf3\xf1\xb5\xb4G9\xe5\
+            \xf0\xed\xed\x18\xd6\xb3?\x04\x0a\x91\x13v\xd1s\xd7W\xa9\xf0\xc1\
+            \xfa\x17\x98\x94\x1b\xb0\xb3k-\xf9\x06'\x90\xfa\x7f\x01f\xf2s~\
+            \xea\x90sx4\x1f\xb1-\xc0\xa7z";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::A);
+        assert_eq!(packet.answers.len(), 2);
+        match packet.answers[0].data {
+            RData::A(addr) => {
+                assert_eq!(addr.0, Ipv4Addr::new(192, 0, 2, 1));
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+        match packet.answers[1].data {
+            RData::RRSIG(sig) => {
+                assert_eq!(sig.type_covered, Type::A);
+                assert_eq!(sig.algorithm, 5);
+                assert_eq!(sig.labels, 3);
+                assert_eq!(sig.original_ttl, 86400);
+                assert_eq!(sig.expiration, 1048354263);  // 20030322173103
+                assert_eq!(sig.inception, 1045762263);  // 20030220173103
+                assert_eq!(sig.key_tag, 2642);
+                assert_eq!(&sig.signer_name.to_string()[..], "example.com");
+                assert_eq!(sig.signature.len(), 128);
+                assert_eq!(&sig.signature[..4], b"\xa0\x90u[");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use super::tlsa;
+
+/// The SMIMEA record (RFC 8162)
+///
+/// It has the same format as the TLSA record but is used to associate
+/// S/MIME certificates with email addresses.
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a>(pub tlsa::Record<'a>);
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 53;
+
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        let record = tlsa::Record::parse_fields(rdata)?;
+        Ok(super::RData::SMIMEA(Record(record)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+    use rdata::tlsa::{CertUsage, Selector, MatchingType};
+
+    #[test]
+    fn parse_response() {
+        let response = b"\xaa\xbb\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x38c93f1e400f26708f98cb19d936620da35eec8f72e57f9eec01c1afd6\
+            \x0a_smimecert\x07example\x03com\x00\x005\x00\x01\
+            \xc0\x0c\x005\x00\x01\x00\x00\x0e\x10\x00\x07\x03\x00\x00\
+            \x30\x82\x01\x0a";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::SMIMEA);
+        match packet.answers[0].data {
+            RData::SMIMEA(smimea) => {
+                assert_eq!(smimea.0.cert_usage, CertUsage::DaneEe);
+                assert_eq!(smimea.0.selector, Selector::Cert);
+                assert_eq!(smimea.0.matching_type, MatchingType::Full);
+                assert_eq!(smimea.0.data, b"\x30\x82\x01\x0a");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use Error;
+
+/// The SSHFP (SSH key fingerprint) record (RFC 4255)
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub algorithm: Algorithm,
+    pub fingerprint_type: FingerprintType,
+    pub fingerprint: &'a [u8],
+}
+
+/// Public key algorithm of SSHFP record
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Algorithm {
+    Rsa,
+    Dsa,
+    /// RFC 6594
+    Ecdsa,
+    /// RFC 7479
+    Ed25519,
+    /// RFC 8709
+    Ed448,
+    /// Any value not listed above
+    Unknown(u8),
+}
+
+/// Fingerprint type of SSHFP record
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FingerprintType {
+    Sha1,
+    /// RFC 6594
+    Sha256,
+    /// Any value not listed above
+    Unknown(u8),
+}
+
+impl From<u8> for Algorithm {
+    fn from(code: u8) -> Algorithm {
+        use self::Algorithm::*;
+        match code {
+            1 => Rsa,
+            2 => Dsa,
+            3 => Ecdsa,
+            4 => Ed25519,
+            6 => Ed448,
+            x => Unknown(x),
+        }
+    }
+}
+impl From<Algorithm> for u8 {
+    fn from(value: Algorithm) -> u8 {
+        use self::Algorithm::*;
+        match value {
+            Rsa         => 1,
+            Dsa         => 2,
+            Ecdsa       => 3,
+            Ed25519     => 4,
+            Ed448       => 6,
+            Unknown(x)  => x,
+        }
+    }
+}
+
+impl From<u8> for FingerprintType {
+    fn from(code: u8) -> FingerprintType {
+        use self::FingerprintType::*;
+        match code {
+            1 => Sha1,
+            2 => Sha256,
+            x => Unknown(x),
+        }
+    }
+}
+impl From<FingerprintType> for u8 {
+    fn from(value: FingerprintType) -> u8 {
+        use self::FingerprintType::*;
+        match value {
+            Sha1        => 1,
+            Sha256      => 2,
+            Unknown(x)  => x,
+        }
+    }
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 44;
+
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        if rdata.len() < 2 {
+            return Err(Error::WrongRdataLength);
+        }
+        let record = Record {
+            algorithm: rdata[0].into(),
+            fingerprint_type: rdata[1].into(),
+            fingerprint: &rdata[2..],
+        };
+        Ok(super::RData::SSHFP(record))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+    use super::*;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\xaa\xbb\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x04host\x07example\x03com\x00\x00,\x00\x01\
+            \xc0\x0c\x00,\x00\x01\x00\x00\x0e\x10\x00\x22\x04\x02\
+            \x9f\x86\xd0\x81\x88L}e\x9a/\xea\xa0\xc5Z\xd0\x15\xa3\xbfO\x1b+\
+            \x0b\x82,\xd1]l\x15\xb0\xf0\n\x08";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::SSHFP);
+        match packet.answers[0].data {
+            RData::SSHFP(sshfp) => {
+                assert_eq!(sshfp.algorithm, Algorithm::Ed25519);
+                assert_eq!(sshfp.fingerprint_type, FingerprintType::Sha256);
+                assert_eq!(sshfp.fingerprint.len(), 32);
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+}
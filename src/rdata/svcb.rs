@@ -0,0 +1,400 @@
+use net::{Ipv4Addr, Ipv6Addr};
+use core::str::from_utf8;
+
+use {Name, Error};
+use byteorder::{BigEndian, ByteOrder};
+
+/// The SVCB (Service Binding) record (RFC 9460)
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    /// Zero means AliasMode, any other value is ServiceMode priority
+    pub priority: u16,
+    pub target: Name<'a>,
+    /// Raw SvcParams, use `params()` to iterate over decoded parameters
+    pub params: &'a [u8],
+}
+
+/// A single service parameter of SVCB or HTTPS record
+#[derive(Debug, Clone)]
+pub enum SvcParam<'a> {
+    /// Keys which must be supported by the client to use this record
+    Mandatory(KeyIter<'a>),
+    /// Supported ALPN protocol identifiers
+    Alpn(AlpnIter<'a>),
+    /// Default ALPN protocol is not supported
+    NoDefaultAlpn,
+    /// Alternative port
+    Port(u16),
+    /// IPv4 addresses that can be used to reach the service
+    Ipv4Hint(Ipv4Iter<'a>),
+    /// ECHConfigList (encrypted client hello configuration)
+    Ech(&'a [u8]),
+    /// IPv6 addresses that can be used to reach the service
+    Ipv6Hint(Ipv6Iter<'a>),
+    /// URI template for DNS over HTTPS (RFC 9461)
+    DohPath(&'a str),
+    /// Any parameter not listed above, with its key and raw value
+    Unknown(u16, &'a [u8]),
+}
+
+/// Iterator over SvcParams of the record
+#[derive(Debug, Clone)]
+pub struct SvcParamIter<'a> {
+    bytes: &'a [u8],
+}
+
+/// Iterator over keys in the `mandatory` parameter
+#[derive(Debug, Clone)]
+pub struct KeyIter<'a> {
+    bytes: &'a [u8],
+}
+
+/// Iterator over protocol identifiers in the `alpn` parameter
+#[derive(Debug, Clone)]
+pub struct AlpnIter<'a> {
+    bytes: &'a [u8],
+}
+
+/// Iterator over addresses in the `ipv4hint` parameter
+#[derive(Debug, Clone)]
+pub struct Ipv4Iter<'a> {
+    bytes: &'a [u8],
+}
+
+/// Iterator over addresses in the `ipv6hint` parameter
+#[derive(Debug, Clone)]
+pub struct Ipv6Iter<'a> {
+    bytes: &'a [u8],
+}
+
+pub const MANDATORY: u16 = 0;
+pub const ALPN: u16 = 1;
+pub const NO_DEFAULT_ALPN: u16 = 2;
+pub const PORT: u16 = 3;
+pub const IPV4HINT: u16 = 4;
+pub const ECH: u16 = 5;
+pub const IPV6HINT: u16 = 6;
+pub const DOHPATH: u16 = 7;
+
+impl<'a> Record<'a> {
+    pub(crate) fn parse_fields(rdata: &'a [u8], original: &'a [u8])
+        -> Result<Record<'a>, Error>
+    {
+        if rdata.len() < 3 {
+            return Err(Error::WrongRdataLength);
+        }
+        let target = Name::scan(&rdata[2..], original)?;
+        let params = &rdata[2+target.byte_len()..];
+        Record::check_params(params)?;
+        Ok(Record {
+            priority: BigEndian::read_u16(&rdata[..2]),
+            target,
+            params,
+        })
+    }
+    /// Checks that parameters are well-formed TLVs in ascending key order
+    ///
+    /// The value of each parameter is only checked when iterating.
+    pub fn check_params(data: &[u8]) -> Result<(), Error> {
+        let mut pos = 0;
+        let mut prev_key = None;
+        while pos < data.len() {
+            if data.len() < pos + 4 {
+                return Err(Error::WrongRdataLength);
+            }
+            let key = BigEndian::read_u16(&data[pos..pos+2]);
+            if prev_key.map(|prev| prev >= key).unwrap_or(false) {
+                return Err(Error::BadSvcParam(key));
+            }
+            prev_key = Some(key);
+            let len = BigEndian::read_u16(&data[pos+2..pos+4]) as usize;
+            pos += 4 + len;
+            if data.len() < pos {
+                return Err(Error::WrongRdataLength);
+            }
+        }
+        Ok(())
+    }
+    /// Returns true if the record is in AliasMode
+    pub fn is_alias(&self) -> bool {
+        self.priority == 0
+    }
+    /// Returns iterator over service parameters
+    pub fn params(&self) -> SvcParamIter<'a> {
+        SvcParamIter {
+            bytes: self.params,
+        }
+    }
+}
+
+impl<'a> Iterator for SvcParamIter<'a> {
+    type Item = Result<SvcParam<'a>, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        if self.bytes.len() < 4 {
+            self.bytes = &[];
+            return Some(Err(Error::WrongRdataLength));
+        }
+        let key = BigEndian::read_u16(&self.bytes[..2]);
+        let len = BigEndian::read_u16(&self.bytes[2..4]) as usize;
+        if self.bytes.len() < len+4 {
+            self.bytes = &[];
+            return Some(Err(Error::WrongRdataLength));
+        }
+        let (head, tail) = self.bytes[4..].split_at(len);
+        self.bytes = tail;
+        Some(SvcParam::parse(key, head))
+    }
+}
+
+impl<'a> SvcParam<'a> {
+    /// Parse value of the parameter with specified key
+    pub fn parse(key: u16, data: &'a [u8]) -> Result<SvcParam<'a>, Error> {
+        let err = Error::BadSvcParam(key);
+        match key {
+            MANDATORY => {
+                if data.is_empty() || data.len() % 2 != 0 {
+                    return Err(err);
+                }
+                Ok(SvcParam::Mandatory(KeyIter { bytes: data }))
+            }
+            ALPN => {
+                if data.is_empty() {
+                    return Err(err);
+                }
+                let mut pos = 0;
+                while pos < data.len() {
+                    let len = data[pos] as usize;
+                    pos += 1 + len;
+                    if len == 0 || pos > data.len() {
+                        return Err(err);
+                    }
+                }
+                Ok(SvcParam::Alpn(AlpnIter { bytes: data }))
+            }
+            NO_DEFAULT_ALPN if data.is_empty() => Ok(SvcParam::NoDefaultAlpn),
+            PORT if data.len() == 2 => {
+                Ok(SvcParam::Port(BigEndian::read_u16(data)))
+            }
+            IPV4HINT if !data.is_empty() && data.len() % 4 == 0 => {
+                Ok(SvcParam::Ipv4Hint(Ipv4Iter { bytes: data }))
+            }
+            ECH => Ok(SvcParam::Ech(data)),
+            IPV6HINT if !data.is_empty() && data.len() % 16 == 0 => {
+                Ok(SvcParam::Ipv6Hint(Ipv6Iter { bytes: data }))
+            }
+            DOHPATH => {
+                from_utf8(data).map(SvcParam::DohPath).map_err(|_| err)
+            }
+            NO_DEFAULT_ALPN | PORT | IPV4HINT | IPV6HINT => Err(err),
+            _ => Ok(SvcParam::Unknown(key, data)),
+        }
+    }
+    /// Returns the parameter key
+    pub fn key(&self) -> u16 {
+        match *self {
+            SvcParam::Mandatory(..)     => MANDATORY,
+            SvcParam::Alpn(..)          => ALPN,
+            SvcParam::NoDefaultAlpn     => NO_DEFAULT_ALPN,
+            SvcParam::Port(..)          => PORT,
+            SvcParam::Ipv4Hint(..)      => IPV4HINT,
+            SvcParam::Ech(..)           => ECH,
+            SvcParam::Ipv6Hint(..)      => IPV6HINT,
+            SvcParam::DohPath(..)       => DOHPATH,
+            SvcParam::Unknown(key, _)   => key,
+        }
+    }
+}
+
+impl<'a> Iterator for KeyIter<'a> {
+    type Item = u16;
+    fn next(&mut self) -> Option<u16> {
+        if self.bytes.len() < 2 {
+            return None;
+        }
+        let key = BigEndian::read_u16(&self.bytes[..2]);
+        self.bytes = &self.bytes[2..];
+        Some(key)
+    }
+}
+
+impl<'a> Iterator for AlpnIter<'a> {
+    type Item = &'a [u8];
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        let len = self.bytes[0] as usize;
+        if self.bytes.len() <= len {
+            self.bytes = &[];
+            return None;
+        }
+        let (head, tail) = self.bytes[1..].split_at(len);
+        self.bytes = tail;
+        Some(head)
+    }
+}
+
+impl<'a> Iterator for Ipv4Iter<'a> {
+    type Item = Ipv4Addr;
+    fn next(&mut self) -> Option<Ipv4Addr> {
+        if self.bytes.len() < 4 {
+            return None;
+        }
+        let addr = Ipv4Addr::from(BigEndian::read_u32(&self.bytes[..4]));
+        self.bytes = &self.bytes[4..];
+        Some(addr)
+    }
+}
+
+impl<'a> Iterator for Ipv6Iter<'a> {
+    type Item = Ipv6Addr;
+    fn next(&mut self) -> Option<Ipv6Addr> {
+        if self.bytes.len() < 16 {
+            return None;
+        }
+        let mut octets = [0u8; 16];
+        octets.copy_from_slice(&self.bytes[..16]);
+        self.bytes = &self.bytes[16..];
+        Some(Ipv6Addr::from(octets))
+    }
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 64;
+
+    fn parse(rdata: &'a [u8], original: &'a [u8]) -> super::RDataResult<'a> {
+        Ok(super::RData::SVCB(Record::parse_fields(rdata, original)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+    use super::*;
+
+    #[test]
+    fn parse_https_response() {
+        let response = b"\xaa\xbb\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x07example\x03com\x00\x00A\x00\x01\
+            \xc0\x0c\x00A\x00\x01\x00\x00\x0e\x10\x003\x00\x01\x00\
+            \x00\x01\x00\x06\x02h2\x02h3\
+            \x00\x04\x00\x08\xc0\x00\x02\x01\xc0\x00\x02\x02\
+            \x00\x05\x00\x02\xab\xcd\
+            \x00\x06\x00\x10\x20\x01\x0d\xb8\x00\x00\x00\x00\
+            \x00\x00\x00\x00\x00\x00\x00\x01";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::HTTPS);
+        let svcb = match packet.answers[0].data {
+            RData::HTTPS(https) => https.0,
+            ref x => panic!("Wrong rdata {:?}", x),
+        };
+        assert!(!svcb.is_alias());
+        assert_eq!(svcb.priority, 1);
+        assert_eq!(&svcb.target.to_string()[..], "");
+        let params = svcb.params()
+            .collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(params.len(), 4);
+        match params[0] {
+            SvcParam::Alpn(ref alpn) => {
+                assert_eq!(alpn.clone().collect::<Vec<_>>(),
+                    vec![&b"h2"[..], &b"h3"[..]]);
+            }
+            ref x => panic!("Wrong param {:?}", x),
+        }
+        match params[1] {
+            SvcParam::Ipv4Hint(ref addrs) => {
+                assert_eq!(addrs.clone().collect::<Vec<_>>(), vec![
+                    Ipv4Addr::new(192, 0, 2, 1),
+                    Ipv4Addr::new(192, 0, 2, 2),
+                ]);
+            }
+            ref x => panic!("Wrong param {:?}", x),
+        }
+        assert_matches!(params[2], SvcParam::Ech(b"\xab\xcd"));
+        match params[3] {
+            SvcParam::Ipv6Hint(ref addrs) => {
+                assert_eq!(addrs.clone().collect::<Vec<_>>(), vec![
+                    "2001:db8::1".parse::<Ipv6Addr>().unwrap(),
+                ]);
+            }
+            ref x => panic!("Wrong param {:?}", x),
+        }
+    }
+
+    #[test]
+    fn parse_svcb_response() {
+        let response = b"\xaa\xbb\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x04_dns\x08resolver\x07example\x00\x00@\x00\x01\
+            \xc0\x0c\x00@\x00\x01\x00\x00\x0e\x10\x00A\x00\x10\
+            \x03doh\x07example\x00\
+            \x00\x00\x00\x04\x00\x01\x00\x03\
+            \x00\x01\x00\x03\x02h2\
+            \x00\x03\x00\x02\x20\xfb\
+            \x00\x07\x00\x10/dns-query{?dns}\
+            \x02\x9b\x00\x05hello";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::SVCB);
+        let svcb = match packet.answers[0].data {
+            RData::SVCB(svcb) => svcb,
+            ref x => panic!("Wrong rdata {:?}", x),
+        };
+        assert_eq!(svcb.priority, 16);
+        assert_eq!(&svcb.target.to_string()[..], "doh.example");
+        let params = svcb.params()
+            .collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(params.iter().map(|p| p.key()).collect::<Vec<_>>(),
+            vec![MANDATORY, ALPN, PORT, DOHPATH, 667]);
+        match params[0] {
+            SvcParam::Mandatory(ref keys) => {
+                assert_eq!(keys.clone().collect::<Vec<_>>(), vec![ALPN, PORT]);
+            }
+            ref x => panic!("Wrong param {:?}", x),
+        }
+        assert_matches!(params[2], SvcParam::Port(8443));
+        assert_matches!(params[3], SvcParam::DohPath("/dns-query{?dns}"));
+        assert_matches!(params[4], SvcParam::Unknown(667, b"hello"));
+    }
+
+    #[test]
+    fn malformed_params() {
+        // keys out of order
+        assert_matches!(Record::check_params(
+            b"\x00\x03\x00\x02\x01\xbb\x00\x01\x00\x03\x02h2"),
+            Err(Error::BadSvcParam(ALPN)));
+        // truncated value
+        assert_matches!(Record::check_params(b"\x00\x03\x00\x02\x01"),
+            Err(Error::WrongRdataLength));
+        assert_matches!(SvcParam::parse(PORT, b"\x01"),
+            Err(Error::BadSvcParam(PORT)));
+        assert_matches!(SvcParam::parse(ALPN, b"\x03h2"),
+            Err(Error::BadSvcParam(ALPN)));
+        assert_matches!(SvcParam::parse(NO_DEFAULT_ALPN, b"x"),
+            Err(Error::BadSvcParam(NO_DEFAULT_ALPN)));
+    }
+
+    #[test]
+    fn unchecked_params() {
+        let rec = Record {
+            priority: 1,
+            target: Name::scan(b"\x00", b"\x00").unwrap(),
+            params: b"\x00\x03\x00\x02\x01\xbb\x00\x04\x00\x08\x01",
+        };
+        let mut params = rec.params();
+        assert_matches!(params.next(), Some(Ok(SvcParam::Port(443))));
+        assert_matches!(params.next(), Some(Err(Error::WrongRdataLength)));
+        assert!(params.next().is_none());
+        let rec = Record { params: b"\x00\x03\x00", ..rec };
+        let mut params = rec.params();
+        assert_matches!(params.next(), Some(Err(Error::WrongRdataLength)));
+        assert!(params.next().is_none());
+    }
+}
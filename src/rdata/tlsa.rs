@@ -0,0 +1,193 @@
+use Error;
+
+/// The TLSA record (RFC 6698)
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub cert_usage: CertUsage,
+    pub selector: Selector,
+    pub matching_type: MatchingType,
+    /// Certificate association data, either full data or its hash
+    /// depending on `matching_type`
+    pub data: &'a [u8],
+}
+
+/// Certificate usage field of TLSA record (RFC 7218 mnemonics)
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CertUsage {
+    /// CA constraint, must be validated by PKIX
+    PkixTa,
+    /// Service certificate constraint, must be validated by PKIX
+    PkixEe,
+    /// Trust anchor assertion
+    DaneTa,
+    /// Domain-issued certificate
+    DaneEe,
+    /// Reserved for private use
+    PrivCert,
+    /// Any value not listed above
+    Unknown(u8),
+}
+
+/// Selector field of TLSA record, which part of certificate is matched
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Selector {
+    /// Full certificate
+    Cert,
+    /// SubjectPublicKeyInfo
+    Spki,
+    /// Reserved for private use
+    PrivSel,
+    /// Any value not listed above
+    Unknown(u8),
+}
+
+/// Matching type field of TLSA record, how certificate data is presented
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MatchingType {
+    /// Exact match on selected content
+    Full,
+    /// SHA-256 hash of selected content
+    Sha256,
+    /// SHA-512 hash of selected content
+    Sha512,
+    /// Reserved for private use
+    PrivMatch,
+    /// Any value not listed above
+    Unknown(u8),
+}
+
+impl From<u8> for CertUsage {
+    fn from(code: u8) -> CertUsage {
+        use self::CertUsage::*;
+        match code {
+            0   => PkixTa,
+            1   => PkixEe,
+            2   => DaneTa,
+            3   => DaneEe,
+            255 => PrivCert,
+            x   => Unknown(x),
+        }
+    }
+}
+impl From<CertUsage> for u8 {
+    fn from(value: CertUsage) -> u8 {
+        use self::CertUsage::*;
+        match value {
+            PkixTa      => 0,
+            PkixEe      => 1,
+            DaneTa      => 2,
+            DaneEe      => 3,
+            PrivCert    => 255,
+            Unknown(x)  => x,
+        }
+    }
+}
+
+impl From<u8> for Selector {
+    fn from(code: u8) -> Selector {
+        use self::Selector::*;
+        match code {
+            0   => Cert,
+            1   => Spki,
+            255 => PrivSel,
+            x   => Unknown(x),
+        }
+    }
+}
+impl From<Selector> for u8 {
+    fn from(value: Selector) -> u8 {
+        use self::Selector::*;
+        match value {
+            Cert        => 0,
+            Spki        => 1,
+            PrivSel     => 255,
+            Unknown(x)  => x,
+        }
+    }
+}
+
+impl From<u8> for MatchingType {
+    fn from(code: u8) -> MatchingType {
+        use self::MatchingType::*;
+        match code {
+            0   => Full,
+            1   => Sha256,
+            2   => Sha512,
+            255 => PrivMatch,
+            x   => Unknown(x),
+        }
+    }
+}
+impl From<MatchingType> for u8 {
+    fn from(value: MatchingType) -> u8 {
+        use self::MatchingType::*;
+        match value {
+            Full        => 0,
+            Sha256      => 1,
+            Sha512      => 2,
+            PrivMatch   => 255,
+            Unknown(x)  => x,
+        }
+    }
+}
+
+impl<'a> Record<'a> {
+    pub(crate) fn parse_fields(rdata: &'a [u8]) -> Result<Record<'a>, Error> {
+        if rdata.len() < 3 {
+            return Err(Error::WrongRdataLength);
+        }
+        Ok(Record {
+            cert_usage: rdata[0].into(),
+            selector: rdata[1].into(),
+            matching_type: rdata[2].into(),
+            data: &rdata[3..],
+        })
+    }
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 52;
+
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        Ok(super::RData::TLSA(Record::parse_fields(rdata)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+    use super::*;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\xaa\xbb\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x04_443\x04_tcp\x03www\x07example\x03com\x00\x004\x00\x01\
+            \xc0\x0c\x004\x00\x01\x00\x00\x0e\x10\x00\x23\x03\x01\x01\
+            \xd2\xab\xde$\r\x7f\xd8\x7f\xd8t\x8e\xc9\x0c\x87t\xb7\xd3\xb0\
+            \x9c\xfe\x1c\x10E\xab\x0f\xa5\x1eQ\xd4\xaa\xc9\xb3\x1c";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::TLSA);
+        match packet.answers[0].data {
+            RData::TLSA(tlsa) => {
+                assert_eq!(tlsa.cert_usage, CertUsage::DaneEe);
+                assert_eq!(tlsa.selector, Selector::Spki);
+                assert_eq!(tlsa.matching_type, MatchingType::Sha256);
+                assert_eq!(tlsa.data.len(), 32);
+                assert_eq!(&tlsa.data[..4], b"\xd2\xab\xde$");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+
+    #[test]
+    fn field_codes() {
+        assert_eq!(CertUsage::from(7), CertUsage::Unknown(7));
+        assert_eq!(u8::from(CertUsage::PrivCert), 255);
+        assert_eq!(u8::from(Selector::from(1)), 1);
+        assert_eq!(MatchingType::from(2), MatchingType::Sha512);
+    }
+}
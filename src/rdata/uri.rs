@@ -0,0 +1,54 @@
+use Error;
+use byteorder::{BigEndian, ByteOrder};
+
+/// The URI record (RFC 7553)
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub priority: u16,
+    pub weight: u16,
+    /// URI as raw bytes, never empty
+    pub target: &'a [u8],
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
+
+    const TYPE: isize = 256;
+
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        if rdata.len() < 5 {
+            return Err(Error::WrongRdataLength);
+        }
+        let record = Record {
+            priority: BigEndian::read_u16(&rdata[..2]),
+            weight: BigEndian::read_u16(&rdata[2..4]),
+            target: &rdata[4..],
+        };
+        Ok(super::RData::URI(record))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\xaa\xbb\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x05_http\x04_tcp\x07example\x03com\x00\x01\x00\x00\x01\
+            \xc0\x0c\x01\x00\x00\x01\x00\x00\x0e\x10\x00\x1b\
+            \x00\x0a\x00\x01http://www.example.com/";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::URI);
+        match packet.answers[0].data {
+            RData::URI(uri) => {
+                assert_eq!(uri.priority, 10);
+                assert_eq!(uri.weight, 1);
+                assert_eq!(uri.target, b"http://www.example.com/");
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
+    }
+}
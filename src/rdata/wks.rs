@@ -1,11 +1,92 @@
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct Record;
+use net::Ipv4Addr;
 
-impl<'a> super::Record<'a> for Record {
+use Error;
+use byteorder::{BigEndian, ByteOrder};
+
+/// The WKS (well known service description) record
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub address: Ipv4Addr,
+    /// IP protocol number, e.g. 6 for TCP and 17 for UDP
+    pub protocol: u8,
+    /// Raw port bitmap, use `ports()` to iterate over port numbers
+    pub bitmap: &'a [u8],
+}
+
+/// Iterator over ports set in the WKS bitmap
+#[derive(Debug, Clone)]
+pub struct PortIter<'a> {
+    bitmap: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Record<'a> {
+    /// Returns iterator over ports of the services provided
+    pub fn ports(&self) -> PortIter<'a> {
+        PortIter {
+            bitmap: self.bitmap,
+            pos: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for PortIter<'a> {
+    type Item = u16;
+    fn next(&mut self) -> Option<u16> {
+        while self.pos < self.bitmap.len()*8 {
+            let pos = self.pos;
+            self.pos += 1;
+            if self.bitmap[pos >> 3] & (0x80 >> (pos & 7)) != 0 {
+                return Some(pos as u16);
+            }
+        }
+        None
+    }
+}
+
+impl<'a> super::Record<'a> for Record<'a> {
 
     const TYPE: isize = 11;
 
-    fn parse(_rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
-        unimplemented!();
+    fn parse(rdata: &'a [u8], _original: &'a [u8]) -> super::RDataResult<'a> {
+        // 8192 bytes are enough for all 65536 ports
+        if rdata.len() < 5 || rdata.len() > 5 + 8192 {
+            return Err(Error::WrongRdataLength);
+        }
+        let record = Record {
+            address: Ipv4Addr::from(BigEndian::read_u32(&rdata[..4])),
+            protocol: rdata[4],
+            bitmap: &rdata[5..],
+        };
+        Ok(super::RData::WKS(record))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::net::Ipv4Addr;
+
+    use Packet;
+    use QueryType as QT;
+    use RData;
+
+    #[test]
+    fn parse_response() {
+        let response = b"\xaa\xbb\x81\x80\x00\x01\x00\x01\x00\x00\x00\x00\
+            \x04host\x07example\x03com\x00\x00\x0b\x00\x01\
+            \xc0\x0c\x00\x0b\x00\x01\x00\x00\x0e\x10\x00\x10\
+            \xc0\x00\x02\x01\x06\
+            \x00\x00\x00\x40\x00\x00\x00\x00\x00\x00\x80";
+        let packet = Packet::parse(response).unwrap();
+        assert_eq!(packet.questions[0].qtype, QT::WKS);
+        match packet.answers[0].data {
+            RData::WKS(wks) => {
+                assert_eq!(wks.address, Ipv4Addr::new(192, 0, 2, 1));
+                assert_eq!(wks.protocol, 6);
+                assert_eq!(wks.ports().collect::<Vec<_>>(), vec![25, 80]);
+            }
+            ref x => panic!("Wrong rdata {:?}", x),
+        }
     }
 }
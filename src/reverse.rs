@@ -0,0 +1,237 @@
+//! Names for reverse lookups (RFC 1035 Section 3.5, RFC 3596 Section 2.5)
+use core::fmt::Write;
+use net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use core::str::from_utf8;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use {Name, OwnedName};
+
+impl OwnedName {
+    /// Returns the `in-addr.arpa` or `ip6.arpa` name of the address
+    ///
+    /// Textual form of the result can be passed to
+    /// `Builder::add_question` to make a PTR query.
+    pub fn from_ip(addr: IpAddr) -> OwnedName {
+        match addr {
+            IpAddr::V4(addr) => OwnedName::from_ipv4(addr),
+            IpAddr::V6(addr) => OwnedName::from_ipv6(addr),
+        }
+    }
+    /// Returns the name like `4.3.2.1.in-addr.arpa` for `1.2.3.4`
+    pub fn from_ipv4(addr: Ipv4Addr) -> OwnedName {
+        let o = addr.octets();
+        format!("{}.{}.{}.{}.in-addr.arpa", o[3], o[2], o[1], o[0])
+            .parse().expect("reverse name is valid")
+    }
+    /// Returns the name in `ip6.arpa` with a label for every nibble
+    pub fn from_ipv6(addr: Ipv6Addr) -> OwnedName {
+        let mut name = String::with_capacity(72);
+        for byte in addr.octets().iter().rev() {
+            write!(name, "{:x}.{:x}.", byte & 0xF, byte >> 4).unwrap();
+        }
+        name.push_str("ip6.arpa");
+        name.parse().expect("reverse name is valid")
+    }
+}
+
+impl<'a> Name<'a> {
+    /// Returns the address if this is a full reverse lookup name
+    ///
+    /// I.e. it's either `in-addr.arpa` name with four labels for the
+    /// octets or `ip6.arpa` name with 32 labels for the nibbles.
+    pub fn to_ip(&self) -> Option<IpAddr> {
+        match self.to_ip_prefix() {
+            Some((addr @ IpAddr::V4(_), 32)) => Some(addr),
+            Some((addr @ IpAddr::V6(_), 128)) => Some(addr),
+            _ => None,
+        }
+    }
+    /// Returns network and prefix length of a reverse lookup name
+    ///
+    /// Names with fewer labels than a full address are accepted, so
+    /// `2.0.192.in-addr.arpa` gives `(192.0.2.0, 24)`. Returns `None` if
+    /// the name is not under `in-addr.arpa` or `ip6.arpa`, or labels are
+    /// not valid octets or nibbles.
+    ///
+    /// The last octet may also be an RFC 2317 classless delegation label,
+    /// either `0/25` or `0-127`, for a prefix of 25 to 31 bits. An address
+    /// inside of such zone, like `5.0/25.2.0.192.in-addr.arpa`, gives
+    /// the full address `(192.0.2.5, 32)`.
+    pub fn to_ip_prefix(&self) -> Option<(IpAddr, u8)> {
+        let labels = self.labels().collect::<Vec<_>>();
+        if labels.len() < 2 ||
+            !labels[labels.len()-1].eq_ignore_ascii_case(b"arpa")
+        {
+            return None;
+        }
+        let parts = &labels[..labels.len()-2];
+        let zone = labels[labels.len()-2];
+        if zone.eq_ignore_ascii_case(b"in-addr") {
+            let mut octets = [0u8; 4];
+            let mut bits = 0;
+            let mut labels = parts.iter().rev();
+            for (octet, label) in octets.iter_mut().zip(&mut labels) {
+                match parse_octet(label) {
+                    Some(value) => {
+                        *octet = value;
+                        bits += 8;
+                    }
+                    None if bits == 24 => {
+                        let (value, len) = parse_classless(label)?;
+                        *octet = value;
+                        bits = len;
+                    }
+                    None => return None,
+                }
+            }
+            if let Some(label) = labels.next() {
+                // Only an address inside of a classless delegation
+                // may have the fifth label
+                let host = parse_octet(label)?;
+                if bits == 32 || labels.next().is_some() ||
+                    host >> (32 - bits) != octets[3] >> (32 - bits)
+                {
+                    return None;
+                }
+                octets[3] = host;
+                bits = 32;
+            }
+            Some((IpAddr::V4(octets.into()), bits))
+        } else if zone.eq_ignore_ascii_case(b"ip6") {
+            if parts.len() > 32 {
+                return None;
+            }
+            let mut octets = [0u8; 16];
+            for (idx, label) in parts.iter().rev().enumerate() {
+                let nibble = parse_nibble(label)?;
+                octets[idx / 2] |= if idx % 2 == 0 { nibble << 4 } else { nibble };
+            }
+            Some((IpAddr::V6(octets.into()), parts.len() as u8 * 4))
+        } else {
+            None
+        }
+    }
+}
+
+fn parse_octet(label: &[u8]) -> Option<u8> {
+    if label.is_empty() || label.len() > 3 ||
+        (label.len() > 1 && label[0] == b'0') ||
+        !label.iter().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    from_utf8(label).ok()?.parse().ok()
+}
+
+/// Parses RFC 2317 label, returns the first octet and prefix length
+fn parse_classless(label: &[u8]) -> Option<(u8, u8)> {
+    let sep = label.iter().position(|&b| b == b'/' || b == b'-')?;
+    let first = parse_octet(&label[..sep])?;
+    let len = if label[sep] == b'/' {
+        parse_octet(&label[sep+1..])?
+    } else {
+        let last = parse_octet(&label[sep+1..])?;
+        let size = (u16::from(last) + 1).checked_sub(u16::from(first))?;
+        if !size.is_power_of_two() {
+            return None;
+        }
+        32 - size.trailing_zeros() as u8
+    };
+    if len <= 24 || len >= 32 || first & (0xFF >> (len - 24)) != 0 {
+        return None;
+    }
+    Some((first, len))
+}
+
+fn parse_nibble(label: &[u8]) -> Option<u8> {
+    match *label {
+        [b] => (b as char).to_digit(16).map(|x| x as u8),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    use {Builder, OwnedName, Packet, QueryType, QueryClass};
+
+    fn prefix(name: &str) -> Option<(IpAddr, u8)> {
+        name.parse::<OwnedName>().unwrap().as_name().to_ip_prefix()
+    }
+
+    #[test]
+    fn ipv4() {
+        let addr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 10));
+        let name = OwnedName::from_ip(addr);
+        assert_eq!(name.to_string(), "10.2.0.192.in-addr.arpa");
+        assert_eq!(name.as_name().to_ip(), Some(addr));
+        assert_eq!(prefix("2.0.192.IN-ADDR.ARPA"),
+                   Some((Ipv4Addr::new(192, 0, 2, 0).into(), 24)));
+        assert_eq!(prefix("in-addr.arpa"),
+                   Some((Ipv4Addr::new(0, 0, 0, 0).into(), 0)));
+        assert_eq!(prefix("256.2.0.192.in-addr.arpa"), None);
+        assert_eq!(prefix("01.2.0.192.in-addr.arpa"), None);
+        assert_eq!(prefix("1.1.2.0.192.in-addr.arpa"), None);
+        assert_eq!(prefix("1.2.0.192.example"), None);
+        assert_eq!("2.0.192.in-addr.arpa".parse::<OwnedName>().unwrap()
+                   .as_name().to_ip(), None);
+    }
+
+    #[test]
+    fn classless() {
+        let net = |last| IpAddr::V4(Ipv4Addr::new(192, 0, 2, last));
+        assert_eq!(prefix("0/25.2.0.192.in-addr.arpa"), Some((net(0), 25)));
+        assert_eq!(prefix("128/26.2.0.192.in-addr.arpa"), Some((net(128), 26)));
+        assert_eq!(prefix("64-127.2.0.192.in-addr.arpa"), Some((net(64), 26)));
+        assert_eq!(prefix("6-7.2.0.192.in-addr.arpa"), Some((net(6), 31)));
+        assert_eq!(prefix("5.0/25.2.0.192.in-addr.arpa"), Some((net(5), 32)));
+        assert_eq!("130.128-191.2.0.192.in-addr.arpa".parse::<OwnedName>()
+                   .unwrap().as_name().to_ip(), Some(net(130)));
+        // host bits set, not aligned or not a power of two
+        assert_eq!(prefix("1/25.2.0.192.in-addr.arpa"), None);
+        assert_eq!(prefix("64-128.2.0.192.in-addr.arpa"), None);
+        assert_eq!(prefix("32-95.2.0.192.in-addr.arpa"), None);
+        assert_eq!(prefix("127-0.2.0.192.in-addr.arpa"), None);
+        // prefix should be longer than the parent zone, shorter than host
+        assert_eq!(prefix("0/24.2.0.192.in-addr.arpa"), None);
+        assert_eq!(prefix("0/32.2.0.192.in-addr.arpa"), None);
+        assert_eq!(prefix("0-255.2.0.192.in-addr.arpa"), None);
+        // address is outside of delegated range
+        assert_eq!(prefix("200.0/25.2.0.192.in-addr.arpa"), None);
+        // only the last octet may be classless
+        assert_eq!(prefix("0/25.0.192.in-addr.arpa"), None);
+        assert_eq!(prefix("1.5.0/25.2.0.192.in-addr.arpa"), None);
+        assert_eq!(prefix("1.5.2.0.192.in-addr.arpa"), None);
+    }
+
+    #[test]
+    fn ipv6() {
+        let addr: IpAddr = "2001:db8::567:89ab".parse().unwrap();
+        let name = OwnedName::from_ip(addr);
+        assert_eq!(name.to_string(), "b.a.9.8.7.6.5.0.0.0.0.0.0.0.0.0.\
+            0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa");
+        assert_eq!(name.as_name().to_ip(), Some(addr));
+        assert_eq!(prefix("8.B.D.0.1.0.0.2.ip6.arpa"),
+                   Some(("2001:db8::".parse::<Ipv6Addr>().unwrap().into(), 32)));
+        assert_eq!(prefix("1.0.0.2.ip6.arpa"),
+                   Some(("2001::".parse::<Ipv6Addr>().unwrap().into(), 16)));
+        assert_eq!(prefix("2.ip6.arpa"),
+                   Some(("2000::".parse::<Ipv6Addr>().unwrap().into(), 4)));
+        assert_eq!(prefix("10.0.0.2.ip6.arpa"), None);
+        assert_eq!(prefix("g.ip6.arpa"), None);
+    }
+
+    #[test]
+    fn ptr_question() {
+        let addr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+        let mut builder = Builder::new_query(1, true);
+        builder.add_question(&OwnedName::from_ip(addr).to_string(), false,
+                             QueryType::PTR, QueryClass::IN);
+        let data = builder.build().unwrap();
+        let packet = Packet::parse(&data).unwrap();
+        assert_eq!(packet.questions[0].qname.to_ip(), Some(addr));
+        assert_eq!(packet.questions[0].qname.to_ip_prefix(), Some((addr, 32)));
+    }
+}
@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use {QueryType, QueryClass, Name, Class, Header, RData};
 use rdata::opt;
 
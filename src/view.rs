@@ -0,0 +1,263 @@
+use {Header, Error, Question, ResourceRecord};
+use parser::{parse_question, parse_record, parse_opt_record, is_opt_record};
+use parser::{skip_question, skip_record};
+use rdata::opt;
+
+/// A DNS packet which is decoded lazily
+///
+/// Unlike `Packet::parse` only the header is parsed upfront, and every
+/// section is available as an iterator which decodes records on demand.
+/// Nothing is allocated on the heap.
+///
+/// Records preceding the section are skipped by their lengths only, so
+/// a malformed record in the authority section isn't noticed when
+/// reading answers. Every call to a section method starts from the
+/// beginning of the packet.
+#[derive(Debug, Clone, Copy)]
+pub struct PacketView<'a> {
+    /// Packet header
+    pub header: Header,
+    data: &'a [u8],
+}
+
+/// Iterator over questions of a `PacketView`
+#[derive(Debug, Clone)]
+pub struct QuestionIter<'a> {
+    data: &'a [u8],
+    offset: usize,
+    remaining: u16,
+}
+
+/// Iterator over records in a single section of a `PacketView`
+///
+/// OPT pseudo-RR is skipped in the additional section, use
+/// `PacketView::opt` to get it.
+#[derive(Debug, Clone)]
+pub struct RecordIter<'a> {
+    data: &'a [u8],
+    offset: usize,
+    skip_questions: u16,
+    skip_records: u32,
+    remaining: u16,
+    additional: bool,
+}
+
+impl<'a> PacketView<'a> {
+    /// Parses packet header, the rest is decoded on demand
+    pub fn parse(data: &'a [u8]) -> Result<PacketView<'a>, Error> {
+        Ok(PacketView {
+            header: Header::parse(data)?,
+            data,
+        })
+    }
+    /// Returns raw bytes of the packet
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+    /// Returns iterator over the question section
+    pub fn questions(&self) -> QuestionIter<'a> {
+        QuestionIter {
+            data: self.data,
+            offset: Header::size(),
+            remaining: self.header.questions,
+        }
+    }
+    /// Returns iterator over the answer section
+    pub fn answers(&self) -> RecordIter<'a> {
+        self.section(0, self.header.answers, false)
+    }
+    /// Returns iterator over the authority section
+    pub fn nameservers(&self) -> RecordIter<'a> {
+        self.section(self.header.answers as u32, self.header.nameservers,
+                     false)
+    }
+    /// Returns iterator over the additional section, excluding OPT
+    pub fn additional(&self) -> RecordIter<'a> {
+        self.section(
+            self.header.answers as u32 + self.header.nameservers as u32,
+            self.header.additional, true)
+    }
+    /// Finds and decodes OPT pseudo-RR in the additional section
+    pub fn opt(&self) -> Result<Option<opt::Record<'a>>, Error> {
+        let mut iter = self.section(
+            self.header.answers as u32 + self.header.nameservers as u32,
+            self.header.additional, false);
+        iter.skip_preceding()?;
+        let mut opt = None;
+        for _ in 0..self.header.additional {
+            if !is_opt_record(self.data, iter.offset) {
+                skip_record(self.data, &mut iter.offset)?;
+            } else if opt.is_none() {
+                opt = Some(parse_opt_record(self.data, &mut iter.offset)?);
+            } else {
+                return Err(Error::AdditionalOPT);
+            }
+        }
+        Ok(opt)
+    }
+    fn section(&self, skip_records: u32, count: u16, additional: bool)
+        -> RecordIter<'a>
+    {
+        RecordIter {
+            data: self.data,
+            offset: Header::size(),
+            skip_questions: self.header.questions,
+            skip_records,
+            remaining: count,
+            additional,
+        }
+    }
+}
+
+impl<'a> Iterator for QuestionIter<'a> {
+    type Item = Result<Question<'a>, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let result = parse_question(self.data, &mut self.offset);
+        if result.is_err() {
+            self.remaining = 0;
+        }
+        Some(result)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining as usize))
+    }
+}
+
+impl<'a> RecordIter<'a> {
+    fn skip_preceding(&mut self) -> Result<(), Error> {
+        while self.skip_questions > 0 {
+            skip_question(self.data, &mut self.offset)?;
+            self.skip_questions -= 1;
+        }
+        while self.skip_records > 0 {
+            skip_record(self.data, &mut self.offset)?;
+            self.skip_records -= 1;
+        }
+        Ok(())
+    }
+    fn next_record(&mut self) -> Option<Result<ResourceRecord<'a>, Error>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        if let Err(e) = self.skip_preceding() {
+            return Some(Err(e));
+        }
+        while self.remaining > 0 {
+            self.remaining -= 1;
+            if self.additional && is_opt_record(self.data, self.offset) {
+                if let Err(e) = skip_record(self.data, &mut self.offset) {
+                    return Some(Err(e));
+                }
+                continue;
+            }
+            return Some(parse_record(self.data, &mut self.offset));
+        }
+        None
+    }
+}
+
+impl<'a> Iterator for RecordIter<'a> {
+    type Item = Result<ResourceRecord<'a>, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.next_record();
+        if let Some(Err(_)) = result {
+            self.remaining = 0;
+        }
+        result
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining as usize))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::Ipv4Addr;
+
+    use {Builder, Error, PacketView, RData, RecordData};
+    use {QueryType as QT, QueryClass as QC, Class as C};
+
+    fn build() -> Vec<u8> {
+        let mut bld = Builder::new_query(19184, true);
+        bld.add_question("www.skype.com", false, QT::A, QC::IN);
+        bld.add_answer("www.skype.com", C::IN, 3600,
+            &RecordData::CNAME("livecms.trafficmanager.net"));
+        bld.add_answer("livecms.trafficmanager.net", C::IN, 60,
+            &RecordData::A(Ipv4Addr::new(192, 0, 2, 1)));
+        bld.add_nameserver("net", C::IN, 120275,
+            &RecordData::NS("g.gtld-servers.net"));
+        bld.add_additional("g.gtld-servers.net", C::IN, 41756,
+            &RecordData::A(Ipv4Addr::new(192, 42, 93, 30)));
+        bld.set_edns_udp_payload_size(1232);
+        bld.build().unwrap()
+    }
+
+    #[test]
+    fn sections() {
+        let data = build();
+        let view = PacketView::parse(&data).unwrap();
+        assert_eq!(view.header.id, 19184);
+        let questions = view.questions()
+            .collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(questions.len(), 1);
+        assert_eq!(questions[0].qname.to_string(), "www.skype.com");
+        assert_eq!(questions[0].qtype, QT::A);
+
+        let mut answers = view.answers();
+        let first = answers.next().unwrap().unwrap();
+        assert_eq!(first.name.to_string(), "www.skype.com");
+        assert_matches!(first.data, RData::CNAME(_));
+        let second = answers.next().unwrap().unwrap();
+        assert_eq!(second.ttl, 60);
+        assert_matches!(second.data, RData::A(_));
+        assert!(answers.next().is_none());
+
+        let ns = view.nameservers()
+            .collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(ns.len(), 1);
+        assert_eq!(ns[0].name.to_string(), "net");
+        let additional = view.additional()
+            .collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(additional.len(), 1);
+        assert_eq!(additional[0].name.to_string(), "g.gtld-servers.net");
+        assert_eq!(view.opt().unwrap().unwrap().udp, 1232);
+    }
+
+    #[test]
+    fn errors_are_lazy() {
+        let data = build();
+        // cut the packet in the middle of the additional section
+        let view = PacketView::parse(&data[..data.len()-20]).unwrap();
+        assert_eq!(view.answers().count(), 2);
+        assert!(view.nameservers().next().unwrap().is_ok());
+        let mut additional = view.additional();
+        assert_matches!(additional.next(), Some(Err(Error::UnexpectedEOF)));
+        assert!(additional.next().is_none());
+        assert_matches!(view.opt(), Err(Error::UnexpectedEOF));
+
+        // cut the packet in the middle of the first answer
+        let view = PacketView::parse(&data[..40]).unwrap();
+        assert_eq!(view.questions().count(), 1);
+        let mut ns = view.nameservers();
+        assert_matches!(ns.next(), Some(Err(Error::UnexpectedEOF)));
+        assert!(ns.next().is_none());
+
+        assert_matches!(PacketView::parse(&data[..11]),
+                        Err(Error::HeaderTooShort));
+    }
+
+    #[test]
+    fn duplicate_opt() {
+        let mut data = build();
+        let opt = data[data.len()-11..].to_vec();
+        data.extend_from_slice(&opt);
+        data[11] += 1;
+        let view = PacketView::parse(&data).unwrap();
+        assert_eq!(view.additional().count(), 1);
+        assert_matches!(view.opt(), Err(Error::AdditionalOPT));
+    }
+}